@@ -1,13 +1,30 @@
 from .__version__ import __description__, __title__, __version__
 from ._api import delete, get, head, options, patch, post, put, request, stream
-from ._auth import Auth, BasicAuth, DigestAuth
+from ._auth import (
+    Auth,
+    BasicAuth,
+    DigestAuth,
+    NegotiateAuth,
+    NTLMAuth,
+    TokenRefreshAuth,
+)
 from ._client import AsyncClient, Client
+from ._clock import Clock, FrozenClock, set_clock, set_random
 from ._config import Limits, Proxy, Timeout, create_ssl_context
 from ._content import ByteStream
+from ._cookiejar import (
+    CallableCookieStorage,
+    CookieStorage,
+    JSONCookieStorage,
+    PersistentCookieJar,
+    SQLiteCookieStorage,
+)
+from ._diff import diff
 from ._exceptions import (
     CloseError,
     ConnectError,
     ConnectTimeout,
+    ContentTypeMismatch,
     CookieConflict,
     DecodingError,
     HTTPError,
@@ -18,6 +35,7 @@ from ._exceptions import (
     PoolTimeout,
     ProtocolError,
     ProxyError,
+    QueryParamsTooLarge,
     ReadError,
     ReadTimeout,
     RemoteProtocolError,
@@ -34,7 +52,19 @@ from ._exceptions import (
     WriteError,
     WriteTimeout,
 )
-from ._models import URL, Cookies, Headers, QueryParams, Request, Response
+from ._models import (
+    URL,
+    Cookies,
+    ETag,
+    Headers,
+    IDNAOptions,
+    QueryParams,
+    QueryParamsBuilder,
+    Request,
+    Response,
+    set_url_cache_size,
+)
+from ._robots import AsyncRobots, Robots, RobotsTxt
 from ._status_codes import codes
 from ._transports.asgi import ASGITransport
 from ._transports.base import (
@@ -44,8 +74,11 @@ from ._transports.base import (
     SyncByteStream,
 )
 from ._transports.default import AsyncHTTPTransport, HTTPTransport
+from ._transports.doh import DoHResolver
+from ._transports.hedging import HedgingTransport
 from ._transports.mock import MockTransport
 from ._transports.wsgi import WSGITransport
+from ._utils import format_content_disposition, parse_quality_values
 
 __all__ = [
     "__description__",
@@ -56,34 +89,51 @@ __all__ = [
     "AsyncByteStream",
     "AsyncClient",
     "AsyncHTTPTransport",
+    "AsyncRobots",
     "Auth",
     "BaseTransport",
     "BasicAuth",
     "ByteStream",
+    "CallableCookieStorage",
     "Client",
+    "Clock",
     "CloseError",
     "codes",
     "ConnectError",
     "ConnectTimeout",
+    "ContentTypeMismatch",
     "CookieConflict",
     "Cookies",
+    "CookieStorage",
     "create_ssl_context",
     "DecodingError",
     "delete",
+    "diff",
     "DigestAuth",
+    "DoHResolver",
+    "ETag",
+    "format_content_disposition",
+    "FrozenClock",
     "get",
     "head",
     "Headers",
+    "HedgingTransport",
     "HTTPError",
     "HTTPStatusError",
     "HTTPTransport",
+    "IDNAOptions",
     "InvalidURL",
+    "JSONCookieStorage",
     "Limits",
     "LocalProtocolError",
     "MockTransport",
+    "NegotiateAuth",
     "NetworkError",
+    "NTLMAuth",
     "options",
+    "parse_quality_values",
     "patch",
+    "PersistentCookieJar",
     "PoolTimeout",
     "post",
     "ProtocolError",
@@ -91,6 +141,8 @@ __all__ = [
     "ProxyError",
     "put",
     "QueryParams",
+    "QueryParamsBuilder",
+    "QueryParamsTooLarge",
     "ReadError",
     "ReadTimeout",
     "RemoteProtocolError",
@@ -100,6 +152,12 @@ __all__ = [
     "RequestNotRead",
     "Response",
     "ResponseNotRead",
+    "Robots",
+    "RobotsTxt",
+    "set_clock",
+    "set_random",
+    "set_url_cache_size",
+    "SQLiteCookieStorage",
     "stream",
     "StreamClosed",
     "StreamConsumed",
@@ -107,6 +165,7 @@ __all__ = [
     "SyncByteStream",
     "Timeout",
     "TimeoutException",
+    "TokenRefreshAuth",
     "TooManyRedirects",
     "TransportError",
     "UnsupportedProtocol",
@@ -1,14 +1,37 @@
+import binascii
 import hashlib
-import os
+import inspect
 import re
+import threading
 import time
 import typing
-from base64 import b64encode
-from urllib.request import parse_http_list
+from base64 import b64decode, b64encode
 
+import sniffio
+
+from ._clock import now, random_bytes
 from ._exceptions import ProtocolError
 from ._models import Request, Response
-from ._utils import to_bytes, to_str, unquote
+from ._ntlm import authenticate_message, negotiate_message, parse_challenge_message
+from ._utils import AuthChallenge, parse_auth_challenges, to_bytes, to_str
+
+if typing.TYPE_CHECKING:  # pragma: no cover
+    import asyncio
+
+    import trio
+
+    Lock = typing.Union[asyncio.Lock, trio.Lock]
+
+
+def create_lock() -> "Lock":
+    if sniffio.current_async_library() == "trio":
+        import trio
+
+        return trio.Lock()
+    else:
+        import asyncio
+
+        return asyncio.Lock()
 
 
 class Auth:
@@ -138,6 +161,115 @@ class BasicAuth(Auth):
         return f"Basic {token}"
 
 
+class TokenRefreshAuth(Auth):
+    """
+    Authenticates requests using a bearer token, which is refreshed and the
+    request sent again if a response indicates that the token has expired.
+
+    ```
+    def refresh_token() -> str:
+        response = httpx.post("https://example.org/token", data={...})
+        return response.json()["access_token"]
+
+    client = httpx.Client(auth=httpx.TokenRefreshAuth(refresh_token))
+    ```
+
+    If several concurrent requests fail, the token is only refreshed once,
+    and each request is then sent again using the new token.
+
+    Arguments:
+
+    * `refresh` - A function returning a new token. May be an `async` function
+      when used with `AsyncClient`.
+    * `token` - *(optional)* The initial token. If not set, `refresh` is called
+      before sending the first request.
+    * `should_refresh` - *(optional)* A function accepting a response, returning
+      `True` if the token should be refreshed. Defaults to checking for a
+      `401 Unauthorized` response.
+    """
+
+    def __init__(
+        self,
+        refresh: typing.Callable[[], typing.Union[str, typing.Awaitable[str]]],
+        token: str = None,
+        should_refresh: typing.Callable[[Response], bool] = None,
+    ) -> None:
+        self._refresh = refresh
+        self._token = token
+        self._should_refresh = (
+            self._is_unauthorized if should_refresh is None else should_refresh
+        )
+        self._sync_lock = threading.Lock()
+        self._async_lock: typing.Optional["Lock"] = None
+
+    def _is_unauthorized(self, response: Response) -> bool:
+        return response.status_code == 401
+
+    def _set_token(self, request: Request, token: str) -> None:
+        request.headers["Authorization"] = f"Bearer {token}"
+
+    def _sync_refresh_token(self, expired: typing.Optional[str]) -> str:
+        with self._sync_lock:
+            # Only refresh the token if another request has not already done so.
+            token = self._token
+            if token is None or token == expired:
+                result = self._refresh()
+                if inspect.isawaitable(result):
+                    raise RuntimeError(
+                        "Attempted to use an async refresh function "
+                        "with a sync Client instance."
+                    )
+                token = self._token = typing.cast(str, result)
+            return token
+
+    async def _async_refresh_token(self, expired: typing.Optional[str]) -> str:
+        if self._async_lock is None:
+            self._async_lock = create_lock()
+        async with self._async_lock:
+            # Only refresh the token if another request has not already done so.
+            token = self._token
+            if token is None or token == expired:
+                result = self._refresh()
+                if inspect.isawaitable(result):
+                    result = await result
+                token = self._token = typing.cast(str, result)
+            return token
+
+    def sync_auth_flow(
+        self, request: Request
+    ) -> typing.Generator[Request, Response, None]:
+        # The request body may need to be sent twice.
+        request.read()
+
+        token = self._token
+        if token is None:
+            token = self._sync_refresh_token(None)
+        self._set_token(request, token)
+        response = yield request
+
+        if self._should_refresh(response):
+            token = self._sync_refresh_token(token)
+            self._set_token(request, token)
+            yield request
+
+    async def async_auth_flow(
+        self, request: Request
+    ) -> typing.AsyncGenerator[Request, Response]:
+        # The request body may need to be sent twice.
+        await request.aread()
+
+        token = self._token
+        if token is None:
+            token = await self._async_refresh_token(None)
+        self._set_token(request, token)
+        response = yield request
+
+        if self._should_refresh(response):
+            token = await self._async_refresh_token(token)
+            self._set_token(request, token)
+            yield request
+
+
 class DigestAuth(Auth):
     _ALGORITHM_TO_HASH_FUNCTION: typing.Dict[str, typing.Callable] = {
         "MD5": hashlib.md5,
@@ -164,35 +296,33 @@ class DigestAuth(Auth):
             # need to build an authenticated request.
             return
 
-        for auth_header in response.headers.get_list("www-authenticate"):
-            if auth_header.lower().startswith("digest "):
-                break
-        else:
+        challenges = [
+            challenge
+            for auth_header in response.headers.get_list("www-authenticate")
+            for challenge in parse_auth_challenges(auth_header)
+            if challenge.scheme == "digest"
+        ]
+        if not challenges:
             # If the response does not include a 'WWW-Authenticate: Digest ...'
-            # header, then we don't need to build an authenticated request.
+            # challenge, then we don't need to build an authenticated request.
             return
 
-        challenge = self._parse_challenge(request, response, auth_header)
+        challenge = self._parse_challenge(request, response, challenges[0])
         request.headers["Authorization"] = self._build_auth_header(request, challenge)
         yield request
 
     def _parse_challenge(
-        self, request: Request, response: Response, auth_header: str
+        self, request: Request, response: Response, auth_challenge: AuthChallenge
     ) -> "_DigestAuthChallenge":
         """
         Returns a challenge from a Digest WWW-Authenticate header.
         These take the form of:
         `Digest realm="realm@host.com",qop="auth,auth-int",nonce="abc",opaque="xyz"`
         """
-        scheme, _, fields = auth_header.partition(" ")
-
-        # This method should only ever have been called with a Digest auth header.
-        assert scheme.lower() == "digest"
+        # This method should only ever have been called with a Digest challenge.
+        assert auth_challenge.scheme == "digest"
 
-        header_dict: typing.Dict[str, str] = {}
-        for field in parse_http_list(fields):
-            key, value = field.strip().split("=", 1)
-            header_dict[key] = unquote(value)
+        header_dict = auth_challenge.params
 
         try:
             realm = header_dict["realm"].encode()
@@ -257,8 +387,8 @@ class DigestAuth(Auth):
     def _get_client_nonce(self, nonce_count: int, nonce: bytes) -> bytes:
         s = str(nonce_count).encode()
         s += nonce
-        s += time.ctime().encode()
-        s += os.urandom(8)
+        s += time.ctime(now()).encode()
+        s += random_bytes(8)
 
         return hashlib.sha1(s).hexdigest()[:16].encode()
 
@@ -302,3 +432,231 @@ class _DigestAuthChallenge(typing.NamedTuple):
     algorithm: str
     opaque: typing.Optional[bytes]
     qop: typing.Optional[bytes]
+
+
+class NegotiateAuth(Auth):
+    """
+    Kerberos/SPNEGO authentication, using the `Negotiate` scheme.
+
+    The GSSAPI token exchange is delegated to `security_context`, which is called
+    with the host name of the server, and should return a `step` function. Each call
+    to `step` is passed the token sent by the server, or `None` for the initial
+    token, and should return the next token to send, or `None` once there are no
+    more tokens to send. For example, using `pyspnego`:
+
+    ```
+    def security_context(host):
+        return spnego.client(hostname=host, service="HTTP").step
+    ```
+
+    With `mutual_authentication` enabled, the final successful response must include
+    a token from the server, which is passed to `step` so that it may verify the
+    identity of the server, raising an exception if verification fails.
+
+    Use `proxy=True` to authenticate with a proxy, using the `Proxy-Authenticate`
+    and `Proxy-Authorization` headers.
+    """
+
+    def __init__(
+        self,
+        security_context: typing.Callable[
+            [str], typing.Callable[[typing.Optional[bytes]], typing.Optional[bytes]]
+        ],
+        mutual_authentication: bool = True,
+        proxy: bool = False,
+    ) -> None:
+        self._security_context = security_context
+        self._mutual_authentication = mutual_authentication
+        if proxy:
+            self._status_code = 407
+            self._challenge_header = "Proxy-Authenticate"
+            self._authorization_header = "Proxy-Authorization"
+        else:
+            self._status_code = 401
+            self._challenge_header = "WWW-Authenticate"
+            self._authorization_header = "Authorization"
+
+    def auth_flow(self, request: Request) -> typing.Generator[Request, Response, None]:
+        response = yield request
+
+        server_token = self._get_server_token(request, response)
+        if response.status_code != self._status_code or server_token is None:
+            # If the response is not a 'Negotiate' challenge then we don't
+            # need to build an authenticated request.
+            return
+
+        step = self._security_context(request.url.host)
+        token = step(None)
+        while token is not None:
+            credentials = b64encode(token).decode("ascii")
+            request.headers[self._authorization_header] = f"Negotiate {credentials}"
+            response = yield request
+
+            server_token = self._get_server_token(request, response)
+            if response.status_code != self._status_code:
+                break
+            elif not server_token:
+                # The server has rejected our credentials.
+                return
+            token = step(server_token)
+
+        if self._mutual_authentication and not response.is_error:
+            if not server_token:
+                message = "Negotiate response did not include a token for mutual auth"
+                raise ProtocolError(message, request=request)
+            step(server_token)
+
+    def _get_server_token(
+        self, request: Request, response: Response
+    ) -> typing.Optional[bytes]:
+        """
+        Returns the token from a Negotiate challenge header, which takes the form of:
+        `Negotiate <base64 token>`, or just `Negotiate` for an initial challenge.
+
+        Returns `None` if the response does not include a Negotiate challenge.
+        """
+        for header in response.headers.get_list(self._challenge_header):
+            for challenge in parse_auth_challenges(header):
+                if challenge.scheme != "negotiate":
+                    continue
+                try:
+                    return b64decode(challenge.token or "", validate=True)
+                except binascii.Error as exc:
+                    message = "Malformed Negotiate token"
+                    raise ProtocolError(message, request=request) from exc
+        return None
+
+
+class NTLMAuth(Auth):
+    """
+    NTLM authentication, using NTLMv2 responses.
+
+    The username may include a Windows domain, as `"DOMAIN\\username"`.
+
+    Since NTLM authenticates a connection, rather than an individual request, the
+    handshake is sent over a single connection, using the "connection_affinity"
+    request extension. Once authenticated, the connection is returned to the pool.
+    If the handshake is abandoned, the connection is closed instead.
+
+    Authenticating with a proxy is not supported, and neither is authenticating
+    with a server through a proxy, since connections made through a proxy are not
+    pinned.
+    """
+
+    def __init__(
+        self, username: typing.Union[str, bytes], password: typing.Union[str, bytes]
+    ) -> None:
+        domain, _, username = to_str(username).rpartition("\\")
+        self._domain = domain
+        self._username = username
+        self._password = to_str(password)
+
+    def auth_flow(self, request: Request) -> typing.Generator[Request, Response, None]:
+        yield from self._handshake(request, {})
+
+    def sync_auth_flow(
+        self, request: Request
+    ) -> typing.Generator[Request, Response, None]:
+        affinity: typing.Dict[str, typing.Any] = {}
+        flow = self._handshake(request, affinity)
+        try:
+            request = next(flow)
+            while True:
+                response = yield request
+                # Read each response, so that a pinned connection is idle if it
+                # needs to be closed once the flow ends.
+                response.read()
+                try:
+                    request = flow.send(response)
+                except StopIteration:
+                    break
+        finally:
+            flow.close()
+            connection = affinity.pop("connection", None)
+            if connection is not None:
+                connection.close()
+
+    async def async_auth_flow(
+        self, request: Request
+    ) -> typing.AsyncGenerator[Request, Response]:
+        affinity: typing.Dict[str, typing.Any] = {}
+        flow = self._handshake(request, affinity)
+        try:
+            request = next(flow)
+            while True:
+                response = yield request
+                await response.aread()
+                try:
+                    request = flow.send(response)
+                except StopIteration:
+                    break
+        finally:
+            flow.close()
+            connection = affinity.pop("connection", None)
+            if connection is not None:
+                await connection.aclose()
+
+    def _handshake(
+        self, request: Request, affinity: typing.Dict[str, typing.Any]
+    ) -> typing.Generator[Request, Response, None]:
+        """
+        Send the NTLM handshake, pinning it to a single connection once the server
+        has issued a challenge. Any connection left pinned by the transport is
+        still in `affinity` when the flow ends, and is closed by the caller.
+        """
+        response = yield request
+
+        server_message = self._get_server_message(request, response)
+        if response.status_code != 401 or server_message is None:
+            # If the response is not an 'NTLM' challenge then we don't
+            # need to build an authenticated request.
+            return
+
+        request.extensions["connection_affinity"] = affinity
+        try:
+            self._set_credentials(request, negotiate_message())
+            response = yield request
+
+            server_message = self._get_server_message(request, response)
+            if response.status_code != 401 or not server_message:
+                return
+
+            try:
+                challenge = parse_challenge_message(server_message)
+            except ValueError as exc:
+                raise ProtocolError(str(exc), request=request) from exc
+
+            message = authenticate_message(
+                challenge, self._username, self._password, domain=self._domain
+            )
+            self._set_credentials(request, message)
+            # This is the final request of the handshake, after which the
+            # connection may be returned to the pool.
+            affinity["release"] = True
+            yield request
+        finally:
+            del request.extensions["connection_affinity"]
+
+    def _set_credentials(self, request: Request, message: bytes) -> None:
+        credentials = b64encode(message).decode("ascii")
+        request.headers["Authorization"] = f"NTLM {credentials}"
+
+    def _get_server_message(
+        self, request: Request, response: Response
+    ) -> typing.Optional[bytes]:
+        """
+        Returns the message from an NTLM challenge header, which takes the form of:
+        `NTLM <base64 message>`, or just `NTLM` for an initial challenge.
+
+        Returns `None` if the response does not include an NTLM challenge.
+        """
+        for header in response.headers.get_list("WWW-Authenticate"):
+            for challenge in parse_auth_challenges(header):
+                if challenge.scheme != "ntlm":
+                    continue
+                try:
+                    return b64decode(challenge.token or "", validate=True)
+                except binascii.Error as exc:
+                    message = "Malformed NTLM message"
+                    raise ProtocolError(message, request=request) from exc
+        return None
@@ -1,5 +1,6 @@
 import datetime
 import enum
+import re
 import typing
 import warnings
 from contextlib import contextmanager
@@ -21,6 +22,7 @@ from ._config import (
 from ._decoders import SUPPORTED_DECODERS
 from ._exceptions import (
     InvalidURL,
+    LocalProtocolError,
     RemoteProtocolError,
     TooManyRedirects,
     request_context,
@@ -51,12 +53,15 @@ from ._types import (
     VerifyTypes,
 )
 from ._utils import (
+    REFERRER_POLICIES,
     NetRCInfo,
     Timer,
     URLPattern,
     get_environment_proxies,
     get_logger,
-    same_origin,
+    parse_content_length,
+    parse_referrer_policy,
+    referrer_for_url,
 )
 
 # The type annotation for @classmethod and context managers here follows PEP 484
@@ -133,6 +138,49 @@ class BoundAsyncStream(AsyncByteStream):
         await self._stream.aclose()
 
 
+class CaptureSyncStream(SyncByteStream):
+    """
+    A request byte stream that records up to `max_size` bytes of the content
+    that is sent, for the "approximate_raw_request" response extension.
+    """
+
+    def __init__(self, stream: SyncByteStream, max_size: int) -> None:
+        self._stream = stream
+        self._max_size = max_size
+        self.prefix = b""
+
+    def __iter__(self) -> typing.Iterator[bytes]:
+        for chunk in self._stream:
+            if len(self.prefix) < self._max_size:
+                self.prefix += chunk[: self._max_size - len(self.prefix)]
+            yield chunk
+
+    def close(self) -> None:
+        self._stream.close()
+
+
+class CaptureAsyncStream(AsyncByteStream):
+    """
+    An async request byte stream that records up to `max_size` bytes of the
+    content that is sent, for the "approximate_raw_request" response
+    extension.
+    """
+
+    def __init__(self, stream: AsyncByteStream, max_size: int) -> None:
+        self._stream = stream
+        self._max_size = max_size
+        self.prefix = b""
+
+    async def __aiter__(self) -> typing.AsyncIterator[bytes]:
+        async for chunk in self._stream:
+            if len(self.prefix) < self._max_size:
+                self.prefix += chunk[: self._max_size - len(self.prefix)]
+            yield chunk
+
+    async def aclose(self) -> None:
+        await self._stream.aclose()
+
+
 class BaseClient:
     def __init__(
         self,
@@ -141,6 +189,14 @@ class BaseClient:
         params: QueryParamTypes = None,
         headers: HeaderTypes = None,
         cookies: CookieTypes = None,
+        scoped_headers: typing.Mapping[str, HeaderTypes] = None,
+        scoped_cookies: typing.Mapping[str, CookieTypes] = None,
+        referrer_policy: str = None,
+        content_sniffing: str = None,
+        capture_approximate_request: int = None,
+        canonical_header_names: bool = False,
+        value_converters: typing.Mapping[type, typing.Callable] = None,
+        mmap_uploads: bool = False,
         timeout: TimeoutTypes = DEFAULT_TIMEOUT_CONFIG,
         max_redirects: int = DEFAULT_MAX_REDIRECTS,
         event_hooks: typing.Mapping[str, typing.List[typing.Callable]] = None,
@@ -151,10 +207,39 @@ class BaseClient:
 
         self._base_url = self._enforce_trailing_slash(URL(base_url))
 
+        self._value_converters = dict(value_converters or {})
         self._auth = self._build_auth(auth)
-        self._params = QueryParams(params)
+        self._params = QueryParams(self._convert_values(params))
         self.headers = Headers(headers)
         self._cookies = Cookies(cookies)
+        self._scoped_headers = {
+            URLPattern(key): Headers(value)
+            for key, value in (scoped_headers or {}).items()
+        }
+        self._scoped_cookies = {
+            URLPattern(key): Cookies(value)
+            for key, value in (scoped_cookies or {}).items()
+        }
+        if referrer_policy is not None and referrer_policy not in REFERRER_POLICIES:
+            raise ValueError(
+                f"Invalid referrer policy {referrer_policy!r}. "
+                f"Must be one of {', '.join(REFERRER_POLICIES)}."
+            )
+        self._referrer_policy = referrer_policy
+        if content_sniffing not in (None, "warn", "raise"):
+            raise ValueError(
+                f"Invalid content_sniffing {content_sniffing!r}. "
+                "Must be one of 'warn', 'raise', or None."
+            )
+        self._content_sniffing = content_sniffing
+        if capture_approximate_request is not None and capture_approximate_request < 0:
+            raise ValueError(
+                f"Invalid capture_approximate_request {capture_approximate_request!r}. "
+                "Must be a number of bytes, or None."
+            )
+        self._capture_approximate_request = capture_approximate_request
+        self._canonical_header_names = canonical_header_names
+        self._mmap_uploads = mmap_uploads
         self._timeout = Timeout(timeout)
         self.max_redirects = max_redirects
         self._event_hooks = {
@@ -288,7 +373,7 @@ class BaseClient:
 
     @params.setter
     def params(self, params: QueryParamTypes) -> None:
-        self._params = QueryParams(params)
+        self._params = QueryParams(self._convert_values(params))
 
     def build_request(
         self,
@@ -315,19 +400,23 @@ class BaseClient:
         [0]: /advanced/#request-instances
         """
         url = self._merge_url(url)
-        headers = self._merge_headers(headers)
-        cookies = self._merge_cookies(cookies)
-        params = self._merge_queryparams(params)
+        headers = self._merge_headers(headers, url=url)
+        if self._referrer_policy is not None and "Referer" in headers:
+            referrer = URL(headers["Referer"])
+            self._set_referer(headers, referrer, url, self._referrer_policy)
+        cookies = self._merge_cookies(cookies, url=url)
+        params = self._merge_queryparams(self._convert_values(params))
         return Request(
             method,
             url,
             content=content,
-            data=data,
+            data=self._convert_values(data),
             files=files,
             json=json,
             params=params,
             headers=headers,
             cookies=cookies,
+            mmap_uploads=self._mmap_uploads,
         )
 
     def _merge_url(self, url: URLTypes) -> URL:
@@ -353,28 +442,56 @@ class BaseClient:
         return merge_url
 
     def _merge_cookies(
-        self, cookies: CookieTypes = None
+        self, cookies: CookieTypes = None, url: URL = None
     ) -> typing.Optional[CookieTypes]:
         """
         Merge a cookies argument together with any cookies on the client,
-        to create the cookies used for the outgoing request.
+        and any scoped cookies matching the URL, to create the cookies used
+        for the outgoing request.
         """
-        if cookies or self.cookies:
+        scoped_cookies = self._scoped_cookies_for_url(url)
+        if cookies or self.cookies or scoped_cookies:
             merged_cookies = Cookies(self.cookies)
+            merged_cookies.update(scoped_cookies)
             merged_cookies.update(cookies)
             return merged_cookies
         return cookies
 
     def _merge_headers(
-        self, headers: HeaderTypes = None
-    ) -> typing.Optional[HeaderTypes]:
+        self, headers: HeaderTypes = None, url: URL = None
+    ) -> Headers:
         """
         Merge a headers argument together with any headers on the client,
-        to create the headers used for the outgoing request.
+        and any scoped headers matching the URL, to create the headers used
+        for the outgoing request.
         """
         merged_headers = Headers(self.headers)
-        merged_headers.update(headers)
-        return merged_headers
+        merged_headers = merged_headers.merge(self._scoped_headers_for_url(url))
+        return merged_headers.merge(headers)
+
+    def _scoped_headers_for_url(self, url: typing.Optional[URL]) -> Headers:
+        """
+        Return the scoped headers that apply to the given URL. Where several
+        patterns match, values from the most specific pattern take precedence.
+        """
+        headers = Headers()
+        if url is not None:
+            for pattern in sorted(self._scoped_headers, reverse=True):
+                if pattern.matches(url):
+                    headers.update(self._scoped_headers[pattern])
+        return headers
+
+    def _scoped_cookies_for_url(self, url: typing.Optional[URL]) -> Cookies:
+        """
+        Return the scoped cookies that apply to the given URL. Where several
+        patterns match, values from the most specific pattern take precedence.
+        """
+        cookies = Cookies()
+        if url is not None:
+            for pattern in sorted(self._scoped_cookies, reverse=True):
+                if pattern.matches(url):
+                    cookies.update(self._scoped_cookies[pattern])
+        return cookies
 
     def _merge_queryparams(
         self, params: QueryParamTypes = None
@@ -389,6 +506,25 @@ class BaseClient:
             return merged_queryparams
         return params
 
+    def _convert_values(self, data: typing.Any) -> typing.Any:
+        """
+        Apply any `value_converters` to the values of query parameters or form
+        data. Query strings and `QueryParams` instances are returned unchanged.
+        """
+        if not self._value_converters or not isinstance(data, (dict, list, tuple)):
+            return data
+        if isinstance(data, dict):
+            return {key: self._convert_value(value) for key, value in data.items()}
+        return [(key, self._convert_value(value)) for key, value in data]
+
+    def _convert_value(self, value: typing.Any) -> typing.Any:
+        if isinstance(value, (list, tuple)):
+            return [self._convert_value(item) for item in value]
+        for cls in type(value).__mro__:
+            if cls in self._value_converters:
+                return self._value_converters[cls](value)
+        return value
+
     def _build_auth(self, auth: AuthTypes) -> typing.Optional[Auth]:
         if auth is None:
             return None
@@ -428,12 +564,58 @@ class BaseClient:
         method = self._redirect_method(request, response)
         url = self._redirect_url(request, response)
         headers = self._redirect_headers(request, url, method)
+        if self._referrer_policy is not None:
+            # The redirect response may set a referrer policy of its own.
+            policy = parse_referrer_policy(response.headers.get("Referrer-Policy", ""))
+            policy = policy or self._referrer_policy
+            self._set_referer(headers, request.url, url, policy)
         stream = self._redirect_stream(request, method)
         cookies = Cookies(self.cookies)
+        cookies.update(self._scoped_cookies_for_url(url))
         return Request(
             method=method, url=url, headers=headers, cookies=cookies, stream=stream
         )
 
+    def _history_extension(
+        self, history: typing.List[Response]
+    ) -> typing.List[typing.Dict[str, typing.Any]]:
+        """
+        Summarise each response in the history of a request, such as followed
+        redirects, so that the time taken and cookies set by each can be audited.
+        """
+        return [
+            {
+                "method": response.request.method,
+                "url": response.url,
+                "status_code": response.status_code,
+                "elapsed": response.elapsed,
+                "set_cookies": [cookie.name for cookie in response.cookies.jar],
+            }
+            for response in history
+        ]
+
+    def _raw_request_headers(
+        self, request: Request
+    ) -> typing.List[typing.Tuple[bytes, bytes]]:
+        """
+        Return the request headers as they are sent, with header names in
+        canonical casing if the client is configured to use it.
+        """
+        if self._canonical_header_names:
+            return request.headers.canonical_raw
+        return request.headers.raw
+
+    def _approximate_request_head(self, request: Request) -> bytes:
+        """
+        Serialize the request line and headers, as they would be sent over
+        HTTP/1.1 directly to the origin. This is a reconstruction, and does not
+        reflect HTTP/2 framing, the absolute-form target used with proxies, or
+        any reordering by a transport's `header_order`.
+        """
+        method, path = request.method.encode(), request.url.raw_path
+        headers = request.headers.to_bytes(canonical=self._canonical_header_names)
+        return b"%b %b HTTP/1.1\r\n%b\r\n" % (method, path, headers)
+
     def _redirect_method(self, request: Request, response: Response) -> str:
         """
         When being redirected we may want to change the method of the request
@@ -463,6 +645,14 @@ class BaseClient:
         """
         location = response.headers["Location"]
 
+        # Handle malformed 'Location' headers that are "absolute" form, have no host.
+        # See: https://github.com/encode/httpx/issues/771
+        match = re.match(r"[a-zA-Z][a-zA-Z0-9+.-]*://(?=[:/?#]|$)", location)
+        if match is not None:
+            host = request.url.raw_host.decode("ascii")
+            host = f"[{host}]" if ":" in host else host
+            location = location[: match.end()] + host + location[match.end() :]
+
         try:
             url = URL(location)
         except InvalidURL as exc:
@@ -470,9 +660,8 @@ class BaseClient:
                 f"Invalid URL in location header: {exc}.", request=request
             ) from None
 
-        # Handle malformed 'Location' headers that are "absolute" form, have no host.
-        # See: https://github.com/encode/httpx/issues/771
-        if url.scheme and not url.host:
+        # Likewise for 'Location' headers with no authority, such as 'https:/path'.
+        if url.scheme and not url.has_host:
             url = url.copy_with(host=request.url.host)
 
         # Facilitate relative 'Location' headers, as allowed by RFC 7231.
@@ -492,7 +681,7 @@ class BaseClient:
         """
         headers = Headers(request.headers)
 
-        if not same_origin(url, request.url):
+        if url.origin != request.url.origin:
             # Strip Authorization headers when responses are redirected away from
             # the origin.
             headers.pop("Authorization", None)
@@ -500,6 +689,18 @@ class BaseClient:
             # Update the Host header.
             headers["Host"] = url.netloc.decode("ascii")
 
+        # Strip any scoped headers that applied to the original URL, and
+        # include those that apply to the redirect URL instead. Headers that
+        # were set explicitly, rather than by the scoped configuration, are kept.
+        original_scoped_headers = self._scoped_headers_for_url(request.url)
+        for key in original_scoped_headers:
+            if headers.get_list(key) == original_scoped_headers.get_list(key):
+                headers.pop(key, None)
+        scoped_headers = self._scoped_headers_for_url(url)
+        for key in scoped_headers:
+            if key not in headers:
+                headers[key] = scoped_headers[key]
+
         if method != request.method and method == "GET":
             # If we've switch to a 'GET' request, then strip any headers which
             # are only relevant to the request body.
@@ -512,6 +713,19 @@ class BaseClient:
 
         return headers
 
+    def _set_referer(
+        self, headers: Headers, referrer: URL, url: URL, policy: str
+    ) -> None:
+        """
+        Set the 'Referer' header for a request to the given URL, following the
+        referrer policy, or remove the header if the policy does not allow it.
+        """
+        referer = referrer_for_url(referrer, url, policy)
+        if referer is None:
+            headers.pop("Referer", None)
+        else:
+            headers["Referer"] = referer
+
     def _redirect_stream(
         self, request: Request, method: str
     ) -> typing.Optional[typing.Union[SyncByteStream, AsyncByteStream]]:
@@ -545,6 +759,33 @@ class Client(BaseClient):
     sending requests.
     * **cookies** - *(optional)* Dictionary of Cookie items to include when
     sending requests.
+    * **scoped_headers** - *(optional)* A dictionary mapping URL patterns to
+    headers that should only be included when sending requests to matching URLs.
+    * **scoped_cookies** - *(optional)* A dictionary mapping URL patterns to
+    cookies that should only be included when sending requests to matching URLs.
+    * **referrer_policy** - *(optional)* A browser-style referrer policy, such as
+    `"strict-origin-when-cross-origin"`, used to set the `Referer` header when
+    following redirects, and to restrict any `Referer` header included on requests.
+    * **content_sniffing** - *(optional)* Either `"warn"` or `"raise"` to check
+    that downloaded archives, executables, and HTML documents match the declared
+    `Content-Type` of the response, issuing a warning or raising
+    `ContentTypeMismatch` when they do not.
+    * **capture_approximate_request** - *(optional)* Record a reconstruction of
+    the request head in HTTP/1.1 form, followed by up to this many bytes of the
+    request body without any chunked framing, as
+    `response.extensions["approximate_raw_request"]`. Use `0` to record only the
+    head. This is not an exact copy of the bytes sent, which may differ in the
+    request target, header order, or HTTP version.
+    * **canonical_header_names** - *(optional)* Send header names in canonical
+    casing, such as `Content-Type`, for servers that treat them case-sensitively.
+    Any casing given in a transport's `header_order` is applied afterwards.
+    * **value_converters** - *(optional)* A dictionary mapping types to functions
+    which convert instances of that type, or of any subclass, into strings when
+    used as query parameter or form values.
+    * **mmap_uploads** - *(optional)* Send files opened in binary mode from
+    memory mapped windows onto the file, rather than reading them, where the
+    platform supports it. The files must not be truncated while they are sent,
+    which crashes the process.
     * **verify** - *(optional)* SSL certificates (a.k.a CA bundle) used to
     verify the identity of requested hosts. Either `True` (default CA bundle),
     a path to an SSL certificate file, an `ssl.SSLContext`, or `False`
@@ -577,6 +818,14 @@ class Client(BaseClient):
         params: QueryParamTypes = None,
         headers: HeaderTypes = None,
         cookies: CookieTypes = None,
+        scoped_headers: typing.Mapping[str, HeaderTypes] = None,
+        scoped_cookies: typing.Mapping[str, CookieTypes] = None,
+        referrer_policy: str = None,
+        content_sniffing: str = None,
+        capture_approximate_request: int = None,
+        canonical_header_names: bool = False,
+        value_converters: typing.Mapping[type, typing.Callable] = None,
+        mmap_uploads: bool = False,
         verify: VerifyTypes = True,
         cert: CertTypes = None,
         http2: bool = False,
@@ -596,6 +845,14 @@ class Client(BaseClient):
             params=params,
             headers=headers,
             cookies=cookies,
+            scoped_headers=scoped_headers,
+            scoped_cookies=scoped_cookies,
+            referrer_policy=referrer_policy,
+            content_sniffing=content_sniffing,
+            capture_approximate_request=capture_approximate_request,
+            canonical_header_names=canonical_header_names,
+            value_converters=value_converters,
+            mmap_uploads=mmap_uploads,
             timeout=timeout,
             max_redirects=max_redirects,
             event_hooks=event_hooks,
@@ -857,32 +1114,35 @@ class Client(BaseClient):
         history: typing.List[Response],
     ) -> Response:
         auth_flow = auth.sync_auth_flow(request)
-        request = next(auth_flow)
+        try:
+            request = next(auth_flow)
 
-        for hook in self._event_hooks["request"]:
-            hook(request)
+            for hook in self._event_hooks["request"]:
+                hook(request)
 
-        while True:
-            response = self._send_handling_redirects(
-                request,
-                timeout=timeout,
-                allow_redirects=allow_redirects,
-                history=history,
-            )
-            try:
+            while True:
+                response = self._send_handling_redirects(
+                    request,
+                    timeout=timeout,
+                    allow_redirects=allow_redirects,
+                    history=history,
+                )
                 try:
-                    next_request = auth_flow.send(response)
-                except StopIteration:
-                    return response
+                    try:
+                        next_request = auth_flow.send(response)
+                    except StopIteration:
+                        return response
 
-                response.history = list(history)
-                response.read()
-                request = next_request
-                history.append(response)
+                    response.history = list(history)
+                    response.read()
+                    request = next_request
+                    history.append(response)
 
-            except Exception as exc:
-                response.close()
-                raise exc
+                except Exception as exc:
+                    response.close()
+                    raise exc
+        finally:
+            auth_flow.close()
 
     def _send_handling_redirects(
         self,
@@ -900,6 +1160,7 @@ class Client(BaseClient):
             response = self._send_single_request(request, timeout)
             try:
                 response.history = list(history)
+                response.extensions["history"] = self._history_extension(history)
 
                 if not response.is_redirect:
                     return response
@@ -930,22 +1191,46 @@ class Client(BaseClient):
                 "Attempted to send an async request with a sync Client instance."
             )
 
+        request_stream: SyncByteStream = request.stream
+        if self._capture_approximate_request is not None:
+            request_stream = CaptureSyncStream(
+                request.stream, max_size=self._capture_approximate_request
+            )
+
         with request_context(request=request):
             (status_code, headers, stream, extensions) = transport.handle_request(
                 request.method.encode(),
                 request.url.raw,
-                headers=request.headers.raw,
-                stream=request.stream,
-                extensions={"timeout": timeout.as_dict()},
+                headers=self._raw_request_headers(request),
+                stream=request_stream,
+                extensions={**request.extensions, "timeout": timeout.as_dict()},
             )
 
-        response = Response(
-            status_code,
-            headers=headers,
-            stream=stream,
-            extensions=extensions,
-            request=request,
-        )
+        if isinstance(request_stream, CaptureSyncStream):
+            head = self._approximate_request_head(request)
+            approximate_request = head + request_stream.prefix
+            extensions = {**extensions, "approximate_raw_request": approximate_request}
+
+        try:
+            response = Response(
+                status_code,
+                headers=headers,
+                stream=stream,
+                extensions=extensions,
+                request=request,
+                content_sniffing=self._content_sniffing,
+            )
+        except LocalProtocolError as exc:
+            # Invalid header names or values in a response were sent by the
+            # server, rather than set by the client.
+            stream.close()
+            raise RemoteProtocolError(str(exc), request=request) from None
+
+        try:
+            parse_content_length(response.headers)
+        except ValueError as exc:
+            stream.close()
+            raise RemoteProtocolError(str(exc), request=request) from None
 
         response.stream = BoundSyncStream(stream, response=response, timer=timer)
         self.cookies.extract_cookies(response)
@@ -1169,6 +1454,32 @@ class Client(BaseClient):
             timeout=timeout,
         )
 
+    def preconnect(self, url: URLTypes, *, count: int = 1) -> None:
+        """
+        Open connections to the origin of the given URL ahead of time, so that
+        latency-critical requests are able to skip connection setup.
+
+        This resolves DNS, opens the TCP connection, and completes any TLS
+        handshake, using the client's timeout configuration. Connections are
+        kept in the connection pool, and are subject to the client's limits.
+        Connections made through a proxy are not preconnected, and a warning
+        is issued instead.
+
+        Usage:
+
+        >>> client = httpx.Client()
+        >>> client.preconnect("https://www.example.com", count=2)
+        """
+        if self._state == ClientState.CLOSED:
+            raise RuntimeError("Cannot preconnect, as the client has been closed.")
+
+        self._state = ClientState.OPENED
+        url = self._merge_url(url)
+        transport = self._transport_for_url(url)
+        transport.preconnect(
+            url.raw, count=count, extensions={"timeout": self.timeout.as_dict()}
+        )
+
     def close(self) -> None:
         """
         Close transport and proxies.
@@ -1230,6 +1541,33 @@ class AsyncClient(BaseClient):
     sending requests.
     * **cookies** - *(optional)* Dictionary of Cookie items to include when
     sending requests.
+    * **scoped_headers** - *(optional)* A dictionary mapping URL patterns to
+    headers that should only be included when sending requests to matching URLs.
+    * **scoped_cookies** - *(optional)* A dictionary mapping URL patterns to
+    cookies that should only be included when sending requests to matching URLs.
+    * **referrer_policy** - *(optional)* A browser-style referrer policy, such as
+    `"strict-origin-when-cross-origin"`, used to set the `Referer` header when
+    following redirects, and to restrict any `Referer` header included on requests.
+    * **content_sniffing** - *(optional)* Either `"warn"` or `"raise"` to check
+    that downloaded archives, executables, and HTML documents match the declared
+    `Content-Type` of the response, issuing a warning or raising
+    `ContentTypeMismatch` when they do not.
+    * **capture_approximate_request** - *(optional)* Record a reconstruction of
+    the request head in HTTP/1.1 form, followed by up to this many bytes of the
+    request body without any chunked framing, as
+    `response.extensions["approximate_raw_request"]`. Use `0` to record only the
+    head. This is not an exact copy of the bytes sent, which may differ in the
+    request target, header order, or HTTP version.
+    * **canonical_header_names** - *(optional)* Send header names in canonical
+    casing, such as `Content-Type`, for servers that treat them case-sensitively.
+    Any casing given in a transport's `header_order` is applied afterwards.
+    * **value_converters** - *(optional)* A dictionary mapping types to functions
+    which convert instances of that type, or of any subclass, into strings when
+    used as query parameter or form values.
+    * **mmap_uploads** - *(optional)* Send files opened in binary mode from
+    memory mapped windows onto the file, rather than reading them, where the
+    platform supports it. The files must not be truncated while they are sent,
+    which crashes the process.
     * **verify** - *(optional)* SSL certificates (a.k.a CA bundle) used to
     verify the identity of requested hosts. Either `True` (default CA bundle),
     a path to an SSL certificate file, or `False` (disable verification).
@@ -1263,6 +1601,14 @@ class AsyncClient(BaseClient):
         params: QueryParamTypes = None,
         headers: HeaderTypes = None,
         cookies: CookieTypes = None,
+        scoped_headers: typing.Mapping[str, HeaderTypes] = None,
+        scoped_cookies: typing.Mapping[str, CookieTypes] = None,
+        referrer_policy: str = None,
+        content_sniffing: str = None,
+        capture_approximate_request: int = None,
+        canonical_header_names: bool = False,
+        value_converters: typing.Mapping[type, typing.Callable] = None,
+        mmap_uploads: bool = False,
         verify: VerifyTypes = True,
         cert: CertTypes = None,
         http2: bool = False,
@@ -1282,6 +1628,14 @@ class AsyncClient(BaseClient):
             params=params,
             headers=headers,
             cookies=cookies,
+            scoped_headers=scoped_headers,
+            scoped_cookies=scoped_cookies,
+            referrer_policy=referrer_policy,
+            content_sniffing=content_sniffing,
+            capture_approximate_request=capture_approximate_request,
+            canonical_header_names=canonical_header_names,
+            value_converters=value_converters,
+            mmap_uploads=mmap_uploads,
             timeout=timeout,
             max_redirects=max_redirects,
             event_hooks=event_hooks,
@@ -1536,32 +1890,35 @@ class AsyncClient(BaseClient):
         history: typing.List[Response],
     ) -> Response:
         auth_flow = auth.async_auth_flow(request)
-        request = await auth_flow.__anext__()
+        try:
+            request = await auth_flow.__anext__()
 
-        for hook in self._event_hooks["request"]:
-            await hook(request)
+            for hook in self._event_hooks["request"]:
+                await hook(request)
 
-        while True:
-            response = await self._send_handling_redirects(
-                request,
-                timeout=timeout,
-                allow_redirects=allow_redirects,
-                history=history,
-            )
-            try:
+            while True:
+                response = await self._send_handling_redirects(
+                    request,
+                    timeout=timeout,
+                    allow_redirects=allow_redirects,
+                    history=history,
+                )
                 try:
-                    next_request = await auth_flow.asend(response)
-                except StopAsyncIteration:
-                    return response
+                    try:
+                        next_request = await auth_flow.asend(response)
+                    except StopAsyncIteration:
+                        return response
 
-                response.history = list(history)
-                await response.aread()
-                request = next_request
-                history.append(response)
+                    response.history = list(history)
+                    await response.aread()
+                    request = next_request
+                    history.append(response)
 
-            except Exception as exc:
-                await response.aclose()
-                raise exc
+                except Exception as exc:
+                    await response.aclose()
+                    raise exc
+        finally:
+            await auth_flow.aclose()
 
     async def _send_handling_redirects(
         self,
@@ -1579,6 +1936,7 @@ class AsyncClient(BaseClient):
             response = await self._send_single_request(request, timeout)
             try:
                 response.history = list(history)
+                response.extensions["history"] = self._history_extension(history)
 
                 if not response.is_redirect:
                     return response
@@ -1611,6 +1969,12 @@ class AsyncClient(BaseClient):
                 "Attempted to send an sync request with an AsyncClient instance."
             )
 
+        request_stream: AsyncByteStream = request.stream
+        if self._capture_approximate_request is not None:
+            request_stream = CaptureAsyncStream(
+                request.stream, max_size=self._capture_approximate_request
+            )
+
         with request_context(request=request):
             (
                 status_code,
@@ -1620,18 +1984,36 @@ class AsyncClient(BaseClient):
             ) = await transport.handle_async_request(
                 request.method.encode(),
                 request.url.raw,
-                headers=request.headers.raw,
-                stream=request.stream,
-                extensions={"timeout": timeout.as_dict()},
+                headers=self._raw_request_headers(request),
+                stream=request_stream,
+                extensions={**request.extensions, "timeout": timeout.as_dict()},
             )
 
-        response = Response(
-            status_code,
-            headers=headers,
-            stream=stream,
-            extensions=extensions,
-            request=request,
-        )
+        if isinstance(request_stream, CaptureAsyncStream):
+            head = self._approximate_request_head(request)
+            approximate_request = head + request_stream.prefix
+            extensions = {**extensions, "approximate_raw_request": approximate_request}
+
+        try:
+            response = Response(
+                status_code,
+                headers=headers,
+                stream=stream,
+                extensions=extensions,
+                request=request,
+                content_sniffing=self._content_sniffing,
+            )
+        except LocalProtocolError as exc:
+            # Invalid header names or values in a response were sent by the
+            # server, rather than set by the client.
+            await stream.aclose()
+            raise RemoteProtocolError(str(exc), request=request) from None
+
+        try:
+            parse_content_length(response.headers)
+        except ValueError as exc:
+            await stream.aclose()
+            raise RemoteProtocolError(str(exc), request=request) from None
 
         response.stream = BoundAsyncStream(stream, response=response, timer=timer)
         self.cookies.extract_cookies(response)
@@ -1855,6 +2237,32 @@ class AsyncClient(BaseClient):
             timeout=timeout,
         )
 
+    async def preconnect(self, url: URLTypes, *, count: int = 1) -> None:
+        """
+        Open connections to the origin of the given URL ahead of time, so that
+        latency-critical requests are able to skip connection setup.
+
+        This resolves DNS, opens the TCP connection, and completes any TLS
+        handshake, using the client's timeout configuration. Connections are
+        kept in the connection pool, and are subject to the client's limits.
+        Connections made through a proxy are not preconnected, and a warning
+        is issued instead.
+
+        Usage:
+
+        >>> client = httpx.AsyncClient()
+        >>> await client.preconnect("https://www.example.com", count=2)
+        """
+        if self._state == ClientState.CLOSED:
+            raise RuntimeError("Cannot preconnect, as the client has been closed.")
+
+        self._state = ClientState.OPENED
+        url = self._merge_url(url)
+        transport = self._transport_for_url(url)
+        await transport.apreconnect(
+            url.raw, count=count, extensions={"timeout": self.timeout.as_dict()}
+        )
+
     async def aclose(self) -> None:
         """
         Close transport and proxies.
@@ -0,0 +1,102 @@
+"""
+Sources of time and randomness, which may be replaced for deterministic testing.
+
+Example usage...
+
+# Freeze time, and seed random values such as multipart boundaries.
+clock = httpx.FrozenClock(time=1600000000.0)
+httpx.set_clock(clock)
+httpx.set_random(random.Random(1234))
+
+# Move time forward, for example to expire any cached robots.txt files.
+clock.advance(24 * 60 * 60)
+
+# Restore the system clock and random number generator.
+httpx.set_clock(None)
+httpx.set_random(None)
+"""
+import os
+import random
+import time
+import typing
+
+
+class Clock:
+    """
+    The system clock.
+
+    To control the time seen by httpx, subclass `Clock` and override the
+    `.time()` and `.monotonic()` methods, or use `FrozenClock`.
+    """
+
+    def time(self) -> float:
+        """
+        Return the current wall-clock time, in seconds since the epoch.
+        """
+        return time.time()
+
+    def monotonic(self) -> float:
+        """
+        Return a monotonic time in seconds, used for measuring durations.
+        """
+        return time.monotonic()
+
+
+class FrozenClock(Clock):
+    """
+    A clock which only moves when `.advance()` is called.
+    """
+
+    def __init__(self, time: float = 0.0) -> None:
+        self._time = time
+        self._monotonic = 0.0
+
+    def time(self) -> float:
+        return self._time
+
+    def monotonic(self) -> float:
+        return self._monotonic
+
+    def advance(self, seconds: float) -> None:
+        self._time += seconds
+        self._monotonic += seconds
+
+
+_clock: Clock = Clock()
+_random: typing.Optional[random.Random] = None
+
+
+def set_clock(clock: typing.Optional[Clock]) -> None:
+    """
+    Set the clock used for cache expiry, cookie expiry, authentication
+    timestamps, and the `.elapsed` time of responses sent by `Client`. Pass
+    `None` to restore the system clock.
+    """
+    global _clock
+    _clock = Clock() if clock is None else clock
+
+
+def set_random(rng: typing.Optional[random.Random]) -> None:
+    """
+    Set the random number generator used for multipart boundaries and
+    authentication nonces. Pass `None` to restore the default generator, which
+    uses `os.urandom()`.
+
+    A seeded generator is predictable, and should only be used for testing.
+    """
+    global _random
+    _random = rng
+
+
+def now() -> float:
+    return _clock.time()
+
+
+def monotonic() -> float:
+    return _clock.monotonic()
+
+
+def random_bytes(length: int) -> bytes:
+    if _random is None:
+        return os.urandom(length)
+    return _random.getrandbits(length * 8).to_bytes(length, "big")
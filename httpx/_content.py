@@ -17,7 +17,7 @@ from ._exceptions import StreamClosed, StreamConsumed
 from ._multipart import MultipartStream
 from ._transports.base import AsyncByteStream, SyncByteStream
 from ._types import RequestContent, RequestData, RequestFiles, ResponseContent
-from ._utils import peek_filelike_length, primitive_value_to_str
+from ._utils import iter_file, peek_filelike_length, primitive_value_to_str
 
 
 class ByteStream(AsyncByteStream, SyncByteStream):
@@ -32,8 +32,9 @@ class ByteStream(AsyncByteStream, SyncByteStream):
 
 
 class IteratorByteStream(SyncByteStream):
-    def __init__(self, stream: Iterable[bytes]):
+    def __init__(self, stream: Iterable[bytes], use_mmap: bool = False):
         self._stream = stream
+        self._use_mmap = use_mmap
         self._is_stream_consumed = False
         self._is_generator = inspect.isgenerator(stream)
 
@@ -42,7 +43,7 @@ class IteratorByteStream(SyncByteStream):
             raise StreamConsumed()
 
         self._is_stream_consumed = True
-        for part in self._stream:
+        for part in iter_file(self._stream, use_mmap=self._use_mmap):
             yield part
 
 
@@ -77,7 +78,8 @@ class UnattachedStream(AsyncByteStream, SyncByteStream):
 
 
 def encode_content(
-    content: Union[str, bytes, Iterable[bytes], AsyncIterable[bytes]]
+    content: Union[str, bytes, Iterable[bytes], AsyncIterable[bytes]],
+    use_mmap: bool = False,
 ) -> Tuple[Dict[str, str], Union[SyncByteStream, AsyncByteStream]]:
 
     if isinstance(content, (bytes, str)):
@@ -93,7 +95,7 @@ def encode_content(
             headers = {"Transfer-Encoding": "chunked"}
         else:
             headers = {"Content-Length": str(content_length_or_none)}
-        return headers, IteratorByteStream(content)  # type: ignore
+        return headers, IteratorByteStream(content, use_mmap)  # type: ignore
 
     elif isinstance(content, AsyncIterable):
         headers = {"Transfer-Encoding": "chunked"}
@@ -119,9 +121,11 @@ def encode_urlencoded_data(
 
 
 def encode_multipart_data(
-    data: dict, files: RequestFiles, boundary: bytes = None
+    data: dict, files: RequestFiles, boundary: bytes = None, use_mmap: bool = False
 ) -> Tuple[Dict[str, str], MultipartStream]:
-    multipart = MultipartStream(data=data, files=files, boundary=boundary)
+    multipart = MultipartStream(
+        data=data, files=files, boundary=boundary, use_mmap=use_mmap
+    )
     headers = multipart.get_headers()
     return headers, multipart
 
@@ -156,10 +160,13 @@ def encode_request(
     files: RequestFiles = None,
     json: Any = None,
     boundary: bytes = None,
+    use_mmap: bool = False,
 ) -> Tuple[Dict[str, str], Union[SyncByteStream, AsyncByteStream]]:
     """
     Handles encoding the given `content`, `data`, `files`, and `json`,
     returning a two-tuple of (<headers>, <stream>).
+
+    Files are memory mapped when they are sent if `use_mmap=True` is set.
     """
     if data is not None and not isinstance(data, dict):
         # We prefer to seperate `content=<bytes|str|byte iterator|bytes aiterator>`
@@ -171,12 +178,12 @@ def encode_request(
         # as if `content=<...>` had been supplied instead.
         message = "Use 'content=<...>' to upload raw bytes/text content."
         warnings.warn(message, DeprecationWarning)
-        return encode_content(data)
+        return encode_content(data, use_mmap)
 
     if content is not None:
-        return encode_content(content)
+        return encode_content(content, use_mmap)
     elif files:
-        return encode_multipart_data(data or {}, files, boundary)
+        return encode_multipart_data(data or {}, files, boundary, use_mmap)
     elif data:
         return encode_urlencoded_data(data)
     elif json is not None:
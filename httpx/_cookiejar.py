@@ -0,0 +1,417 @@
+"""
+Cookie jars that persist their cookies to storage, so that cookies are
+retained across separate runs of a program.
+
+Example usage...
+
+# Persist cookies to a JSON file.
+jar = httpx.PersistentCookieJar(httpx.JSONCookieStorage("cookies.json"))
+client = httpx.Client(cookies=jar)
+
+# Persist cookies to an SQLite database.
+jar = httpx.PersistentCookieJar(httpx.SQLiteCookieStorage("cookies.db"))
+client = httpx.Client(cookies=jar)
+
+# Persist cookies to any other store, using a pair of callables.
+storage = httpx.CallableCookieStorage(load=load_cookies, save=save_cookies)
+client = httpx.Client(cookies=httpx.PersistentCookieJar(storage))
+"""
+import contextlib
+import email.message
+import json
+import os
+import sqlite3
+import tempfile
+import typing
+from http.cookiejar import (
+    Cookie,
+    CookieJar,
+    CookiePolicy,
+    DefaultCookiePolicy,
+    http2time,
+)
+
+from ._clock import now
+
+# The `http.cookiejar.Cookie` attributes that are persisted to storage.
+COOKIE_FIELDS = (
+    "version",
+    "name",
+    "value",
+    "port",
+    "port_specified",
+    "domain",
+    "domain_specified",
+    "domain_initial_dot",
+    "path",
+    "path_specified",
+    "secure",
+    "expires",
+    "discard",
+    "comment",
+    "comment_url",
+    "rfc2109",
+)
+
+
+def cookie_to_dict(cookie: Cookie) -> typing.Dict[str, typing.Any]:
+    data = {field: getattr(cookie, field) for field in COOKIE_FIELDS}
+    data["rest"] = cookie._rest  # type: ignore
+    return data
+
+
+def cookie_from_dict(data: typing.Dict[str, typing.Any]) -> Cookie:
+    return Cookie(**data)
+
+
+def cookie_key(cookie: Cookie) -> typing.Tuple[str, str, str]:
+    return (cookie.domain, cookie.path, cookie.name)
+
+
+class CookieStorage:
+    """
+    Base class for cookie storage backends.
+
+    To implement a custom storage backend, subclass `CookieStorage` and
+    override the `.load()` and `.save()` methods. Backends which can store
+    individual cookies may also override `.update()`.
+    """
+
+    def load(self) -> typing.List[Cookie]:
+        """
+        Return all cookies held in storage.
+        """
+        raise NotImplementedError(
+            "The 'load' method must be implemented."
+        )  # pragma: nocover
+
+    def save(self, cookies: typing.List[Cookie]) -> None:
+        """
+        Replace all cookies held in storage with the given cookies.
+        """
+        raise NotImplementedError(
+            "The 'save' method must be implemented."
+        )  # pragma: nocover
+
+    def update(
+        self,
+        cookies: typing.List[Cookie],
+        changed: typing.List[Cookie],
+        removed: typing.List[typing.Tuple[str, str, str]],
+    ) -> None:
+        """
+        Save the cookies which have changed since the last save. `changed` holds
+        cookies which were added or replaced, and `removed` the
+        `(domain, path, name)` keys of cookies which were removed.
+
+        By default, all cookies held in storage are replaced with `cookies`.
+        """
+        self.save(cookies)
+
+
+class JSONCookieStorage(CookieStorage):
+    """
+    Stores cookies in a JSON file. The file is replaced atomically on each save.
+    """
+
+    def __init__(self, path: typing.Union[str, "os.PathLike[str]"]) -> None:
+        self.path = os.fspath(path)
+
+    def load(self) -> typing.List[Cookie]:
+        try:
+            with open(self.path, encoding="utf-8") as file:
+                data = json.load(file)
+        except FileNotFoundError:
+            return []
+        return [cookie_from_dict(item) for item in data]
+
+    def save(self, cookies: typing.List[Cookie]) -> None:
+        data = [cookie_to_dict(cookie) for cookie in cookies]
+        directory = os.path.dirname(os.path.abspath(self.path))
+        fd, temp_path = tempfile.mkstemp(dir=directory, suffix=".tmp")
+        try:
+            with os.fdopen(fd, "w", encoding="utf-8") as file:
+                json.dump(data, file, indent=2)
+            os.replace(temp_path, self.path)
+        except BaseException:
+            os.unlink(temp_path)
+            raise
+
+
+class SQLiteCookieStorage(CookieStorage):
+    """
+    Stores cookies in an SQLite database, using a single `cookies` table. Each
+    change writes only the rows of the cookies which changed.
+    """
+
+    def __init__(self, path: typing.Union[str, "os.PathLike[str]"]) -> None:
+        self.path = os.fspath(path)
+        with contextlib.closing(sqlite3.connect(self.path)) as connection:
+            with connection:
+                connection.execute(
+                    "CREATE TABLE IF NOT EXISTS cookies ("
+                    "domain TEXT NOT NULL, path TEXT NOT NULL, name TEXT NOT NULL, "
+                    "data TEXT NOT NULL, PRIMARY KEY (domain, path, name))"
+                )
+
+    def load(self) -> typing.List[Cookie]:
+        with contextlib.closing(sqlite3.connect(self.path)) as connection:
+            rows = connection.execute("SELECT data FROM cookies").fetchall()
+        return [cookie_from_dict(json.loads(data)) for (data,) in rows]
+
+    def save(self, cookies: typing.List[Cookie]) -> None:
+        with contextlib.closing(sqlite3.connect(self.path)) as connection:
+            with connection:
+                connection.execute("DELETE FROM cookies")
+                self._insert(connection, cookies)
+
+    def update(
+        self,
+        cookies: typing.List[Cookie],
+        changed: typing.List[Cookie],
+        removed: typing.List[typing.Tuple[str, str, str]],
+    ) -> None:
+        with contextlib.closing(sqlite3.connect(self.path)) as connection:
+            with connection:
+                connection.executemany(
+                    "DELETE FROM cookies WHERE domain = ? AND path = ? AND name = ?",
+                    removed,
+                )
+                self._insert(connection, changed)
+
+    def _insert(
+        self, connection: sqlite3.Connection, cookies: typing.List[Cookie]
+    ) -> None:
+        rows = [
+            (*cookie_key(cookie), json.dumps(cookie_to_dict(cookie)))
+            for cookie in cookies
+        ]
+        connection.executemany(
+            "INSERT OR REPLACE INTO cookies (domain, path, name, data) "
+            "VALUES (?, ?, ?, ?)",
+            rows,
+        )
+
+
+class CallableCookieStorage(CookieStorage):
+    """
+    Stores cookies using a pair of callables, such as functions reading from
+    and writing to an existing database or key-value store.
+
+    `load()` returns, and `save(cookies)` is passed, a list of dictionaries
+    holding the attributes of each cookie. These contain only JSON-compatible
+    values.
+    """
+
+    def __init__(
+        self,
+        load: typing.Callable[[], typing.List[typing.Dict[str, typing.Any]]],
+        save: typing.Callable[[typing.List[typing.Dict[str, typing.Any]]], None],
+    ) -> None:
+        self._load = load
+        self._save = save
+
+    def load(self) -> typing.List[Cookie]:
+        return [cookie_from_dict(data) for data in self._load()]
+
+    def save(self, cookies: typing.List[Cookie]) -> None:
+        self._save([cookie_to_dict(cookie) for cookie in cookies])
+
+
+class SetCookieResponse:
+    """
+    A response with a single `Set-Cookie` header, as used by
+    `CookieJar.make_cookies()`.
+    """
+
+    def __init__(self, header: str) -> None:
+        self._info = email.message.Message()
+        self._info["Set-Cookie"] = header
+
+    def info(self) -> email.message.Message:
+        return self._info
+
+
+class ClockCookiePolicy(DefaultCookiePolicy):
+    """
+    The default cookie policy, checking cookie expiry using the clock set by
+    `httpx.set_clock()` rather than `time.time()`.
+    """
+
+    def set_ok(self, cookie: Cookie, request: typing.Any) -> bool:
+        if cookie.is_expired(int(now())):
+            return False
+        return super().set_ok(cookie, request)
+
+    def return_ok_expires(self, cookie: Cookie, request: typing.Any) -> bool:
+        return not cookie.is_expired(int(now()))
+
+
+class ClockCookieJar(CookieJar):
+    """
+    A cookie jar which uses the clock set by `httpx.set_clock()` for cookie
+    expiry, including the `Max-Age` and `Expires` attributes of received cookies.
+    """
+
+    def __init__(self, policy: CookiePolicy = None) -> None:
+        super().__init__(ClockCookiePolicy() if policy is None else policy)
+
+    def make_cookies(
+        self, response: typing.Any, request: typing.Any
+    ) -> typing.List[Cookie]:
+        """
+        Return the cookies set by the `Set-Cookie` headers of a response.
+
+        `CookieJar` applies the `Max-Age` and `Expires` attributes using
+        `time.time()`, so each header is parsed without them, and its expiry is
+        applied here using the clock instead. Obsolete `Set-Cookie2` headers
+        are ignored.
+        """
+        current_time = int(now())
+        cookies = []
+        for header in response.info().get_all("Set-Cookie", []):
+            name_value, *attributes = header.split(";")
+            parts = [name_value]
+            expires: typing.Optional[float] = None
+            max_age: typing.Optional[str] = None
+            for attribute in attributes:
+                key, _, value = attribute.partition("=")
+                key = key.strip().lower()
+                if key == "expires":
+                    expires = http2time(value.strip().strip('"'))
+                elif key == "max-age":
+                    max_age = value.strip()
+                else:
+                    parts.append(attribute)
+
+            if max_age is not None:
+                try:
+                    expires = current_time + int(max_age)
+                except ValueError:
+                    continue
+
+            set_cookie_response: typing.Any = SetCookieResponse(";".join(parts))
+            for cookie in super().make_cookies(set_cookie_response, request):
+                if expires is not None:
+                    if expires <= current_time:
+                        # An expiry in the past is a request to delete the cookie.
+                        with contextlib.suppress(KeyError):
+                            self.clear(*cookie_key(cookie))
+                        continue
+                    cookie.expires = int(expires)
+                    cookie.discard = False
+                cookies.append(cookie)
+        return cookies
+
+    def clear_expired_cookies(self) -> None:
+        with self._cookies_lock:  # type: ignore
+            current_time = now()
+            for cookie in list(self):
+                if cookie.is_expired(current_time):
+                    self.clear(cookie.domain, cookie.path, cookie.name)
+
+
+class PersistentCookieJar(ClockCookieJar):
+    """
+    A cookie jar that loads its cookies from storage when created, and saves
+    them back to storage whenever they change.
+
+    Expired cookies are neither loaded nor saved. Session cookies are saved,
+    so that they may be reused by later runs of a long-running program.
+    """
+
+    def __init__(self, storage: CookieStorage, policy: CookiePolicy = None) -> None:
+        super().__init__(policy)
+        self.storage = storage
+        self._save_deferred = False
+        # The cookies changed since the last save, or `None` for removed cookies.
+        self._changes: typing.Dict[
+            typing.Tuple[str, str, str], typing.Optional[Cookie]
+        ] = {}
+        current_time = int(now())
+        for cookie in storage.load():
+            if not cookie.is_expired(current_time):
+                super().set_cookie(cookie)
+
+    def save(self) -> None:
+        """
+        Save all unexpired cookies to storage.
+        """
+        with self._cookies_lock:  # type: ignore
+            self._changes.clear()
+            self.storage.save(self._unexpired_cookies())
+
+    def _unexpired_cookies(self) -> typing.List[Cookie]:
+        current_time = int(now())
+        return [cookie for cookie in self if not cookie.is_expired(current_time)]
+
+    def _save_changes(self) -> None:
+        """
+        Save the cookies changed since the last save. Cookies which have since
+        expired are removed from storage.
+        """
+        current_time = int(now())
+        changes, self._changes = self._changes, {}
+        changed = []
+        removed = []
+        for key, cookie in changes.items():
+            if cookie is None or cookie.is_expired(current_time):
+                removed.append(key)
+            else:
+                changed.append(cookie)
+        self.storage.update(self._unexpired_cookies(), changed, removed)
+
+    @contextlib.contextmanager
+    def _deferred_save(self) -> typing.Iterator[None]:
+        """
+        Save any changes once, at the end of an operation which may otherwise
+        modify many cookies individually.
+        """
+        with self._cookies_lock:  # type: ignore
+            if self._save_deferred:
+                yield
+                return
+
+            self._save_deferred = True
+            try:
+                yield
+            finally:
+                self._save_deferred = False
+            if self._changes:
+                self._save_changes()
+
+    def _changed(self) -> None:
+        if not self._save_deferred:
+            self._save_changes()
+
+    def set_cookie(self, cookie: Cookie) -> None:
+        with self._cookies_lock:  # type: ignore
+            super().set_cookie(cookie)
+            self._changes[cookie_key(cookie)] = cookie
+            self._changed()
+
+    def clear(self, domain: str = None, path: str = None, name: str = None) -> None:
+        with self._cookies_lock:  # type: ignore
+            removed = [
+                cookie_key(cookie)
+                for cookie in self
+                if (domain is None or cookie.domain == domain)
+                and (path is None or cookie.path == path)
+                and (name is None or cookie.name == name)
+            ]
+            super().clear(domain, path, name)
+            for key in removed:
+                self._changes[key] = None
+            self._changed()
+
+    def extract_cookies(self, response: typing.Any, request: typing.Any) -> None:
+        with self._deferred_save():
+            super().extract_cookies(response, request)
+
+    def clear_session_cookies(self) -> None:
+        with self._deferred_save():
+            super().clear_session_cookies()
+
+    def clear_expired_cookies(self) -> None:
+        with self._deferred_save():
+            super().clear_expired_cookies()
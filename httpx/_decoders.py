@@ -9,6 +9,7 @@ import typing
 import zlib
 
 from ._exceptions import DecodingError
+from ._utils import parse_content_range, parse_content_type
 
 try:
     import brotlicffi
@@ -357,6 +358,137 @@ class LineDecoder:
         return lines
 
 
+class MultipartDecoder:
+    """
+    Handles incrementally parsing a multipart body, such as `multipart/byteranges`.
+
+    Returns a list of events, where a list of `(name, value)` header pairs marks
+    the start of a new part, and any `bytes` are content for the current part.
+
+    See: https://tools.ietf.org/html/rfc2046#section-5.1.1
+    """
+
+    def __init__(self, boundary: bytes) -> None:
+        self.delimiter = b"\r\n--" + boundary
+        # Prefix the buffer with a CRLF, so that a delimiter at the very start
+        # of the body is matched in the same way as any other delimiter.
+        self.buffer = b"\r\n"
+        self.state = "preamble"
+
+    def decode(self, data: bytes) -> typing.List[typing.Any]:
+        events: typing.List[typing.Any] = []
+        self.buffer += data
+
+        while True:
+            if self.state in ("preamble", "body"):
+                idx = self.buffer.find(self.delimiter)
+                if idx == -1:
+                    # Keep enough of the buffer to match a partial delimiter.
+                    keep = len(self.delimiter) - 1
+                    if self.state == "body" and len(self.buffer) > keep:
+                        events.append(self.buffer[:-keep])
+                    self.buffer = self.buffer[-keep:]
+                    break
+                if self.state == "body" and idx:
+                    events.append(self.buffer[:idx])
+                self.buffer = self.buffer[idx + len(self.delimiter) :]
+                self.state = "delimiter"
+
+            elif self.state == "delimiter":
+                if len(self.buffer) < 2:
+                    break
+                if self.buffer.startswith(b"--"):
+                    self.state = "epilogue"
+                    continue
+                idx = self.buffer.find(b"\r\n")
+                if idx == -1:
+                    break
+                # Skip over any transport padding following the delimiter.
+                self.buffer = self.buffer[idx:]
+                self.state = "headers"
+
+            elif self.state == "headers":
+                idx = self.buffer.find(b"\r\n\r\n")
+                if idx == -1:
+                    break
+                headers = []
+                for line in self.buffer[2:idx].split(b"\r\n"):
+                    if line:
+                        name, sep, value = line.partition(b":")
+                        if not sep:
+                            raise DecodingError("Malformed multipart header")
+                        headers.append((name.strip(), value.strip()))
+                events.append(headers)
+                self.buffer = self.buffer[idx + 4 :]
+                self.state = "body"
+
+            else:  # "epilogue"
+                self.buffer = b""
+                break
+
+        return events
+
+    def flush(self) -> typing.List[typing.Any]:
+        if self.state != "epilogue":
+            raise DecodingError("Incomplete multipart body")
+        return []
+
+
+class ByteRangeDecoder:
+    """
+    Handles incrementally decoding partial content into `(offset, data)` pairs,
+    for either a single `Content-Range`, or a `multipart/byteranges` body.
+
+    See: https://tools.ietf.org/html/rfc7233#section-4.1
+    """
+
+    def __init__(self, content_type: str = "", content_range: str = None) -> None:
+        parsed = parse_content_type(content_type)
+        self.multipart: typing.Optional[MultipartDecoder] = None
+        self.offset = 0
+        if parsed.media_type == "multipart/byteranges":
+            if "boundary" not in parsed.params:
+                raise DecodingError("Missing boundary for multipart/byteranges")
+            self.multipart = MultipartDecoder(parsed.params["boundary"].encode("ascii"))
+        elif content_range is not None:
+            self.offset = self._parse_offset(content_range)
+
+    def decode(self, data: bytes) -> typing.List[typing.Tuple[int, bytes]]:
+        if self.multipart is None:
+            return self._advance(data)
+
+        ranges = []
+        for event in self.multipart.decode(data):
+            if isinstance(event, bytes):
+                ranges.extend(self._advance(event))
+                continue
+            for name, value in event:
+                if name.lower() == b"content-range":
+                    self.offset = self._parse_offset(value.decode("latin-1"))
+                    break
+            else:
+                raise DecodingError("Missing Content-Range in multipart/byteranges")
+        return ranges
+
+    def flush(self) -> typing.List[typing.Tuple[int, bytes]]:
+        if self.multipart is not None:
+            self.multipart.flush()
+        return []
+
+    def _advance(self, data: bytes) -> typing.List[typing.Tuple[int, bytes]]:
+        if not data:
+            return []
+        offset = self.offset
+        self.offset += len(data)
+        return [(offset, data)]
+
+    def _parse_offset(self, content_range: str) -> int:
+        try:
+            return parse_content_range(content_range)[0]
+        except ValueError as exc:
+            raise DecodingError(str(exc)) from exc
+
+
 SUPPORTED_DECODERS = {
     "identity": IdentityDecoder,
     "gzip": GZipDecoder,
@@ -0,0 +1,96 @@
+"""
+Structural comparison of requests and responses, for use in tests.
+"""
+import difflib
+import json
+import typing
+
+from ._models import URL, Headers, Request, Response
+
+
+def diff(
+    expected: typing.Union[Request, Response], actual: typing.Union[Request, Response]
+) -> str:
+    """
+    Compare two requests, or two responses, returning a readable report of any
+    differences, or an empty string if they are equivalent.
+
+    Requests are compared by method, URL, headers, and content. The order of
+    query parameters and headers is ignored, as is the case of header names.
+    Responses are compared by status code, headers, and content. Content is
+    compared as JSON if both are valid JSON, and otherwise as text or bytes.
+    """
+    lines = []
+    if isinstance(expected, Request) and isinstance(actual, Request):
+        if expected.method != actual.method:
+            lines.append(f"method: {expected.method!r} != {actual.method!r}")
+        expected_url = normalize_url(expected.url)
+        actual_url = normalize_url(actual.url)
+        if expected_url != actual_url:
+            lines.append(f"url: {expected_url!r} != {actual_url!r}")
+    elif isinstance(expected, Response) and isinstance(actual, Response):
+        if expected.status_code != actual.status_code:
+            lines.append(
+                f"status_code: {expected.status_code!r} != {actual.status_code!r}"
+            )
+    else:
+        raise TypeError("Expected either two requests, or two responses.")
+
+    lines.extend(diff_headers(expected.headers, actual.headers))
+    lines.extend(diff_content(expected.content, actual.content))
+    return "\n".join(lines)
+
+
+def normalize_url(url: URL) -> str:
+    """
+    Return the string form of a URL, with its query parameters sorted.
+    """
+    if url.query:
+        url = url.copy_with(params=url.params.sorted())
+    return str(url)
+
+
+def diff_headers(expected: Headers, actual: Headers) -> typing.List[str]:
+    lines = []
+    names = sorted({key.lower() for key in [*expected.keys(), *actual.keys()]})
+    for name in names:
+        expected_values = sorted(expected.get_list(name))
+        actual_values = sorted(actual.get_list(name))
+        if expected_values != actual_values:
+            lines.append(f"header {name!r}: {expected_values!r} != {actual_values!r}")
+    return lines
+
+
+def diff_content(expected: bytes, actual: bytes) -> typing.List[str]:
+    if expected == actual:
+        return []
+
+    try:
+        expected_json = json.loads(expected)
+        actual_json = json.loads(actual)
+    except ValueError:
+        pass
+    else:
+        if expected_json == actual_json:
+            return []
+        expected_text = json.dumps(expected_json, indent=2, sort_keys=True)
+        actual_text = json.dumps(actual_json, indent=2, sort_keys=True)
+        return ["json:", *unified_diff(expected_text, actual_text)]
+
+    try:
+        expected_text = expected.decode("utf-8")
+        actual_text = actual.decode("utf-8")
+    except UnicodeDecodeError:
+        return [f"content: {expected!r} != {actual!r}"]
+    return ["content:", *unified_diff(expected_text, actual_text)]
+
+
+def unified_diff(expected: str, actual: str) -> typing.List[str]:
+    lines = difflib.unified_diff(
+        expected.splitlines(),
+        actual.splitlines(),
+        fromfile="expected",
+        tofile="actual",
+        lineterm="",
+    )
+    return ["    " + line for line in lines]
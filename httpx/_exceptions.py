@@ -23,7 +23,9 @@ Our exception hierarchy:
     + TooManyRedirects
     + RequestBodyUnavailable
   x HTTPStatusError
+  x ContentTypeMismatch
 * InvalidURL
+* QueryParamsTooLarge
 * CookieConflict
 * StreamError
   x StreamConsumed
@@ -236,9 +238,44 @@ class HTTPStatusError(HTTPError):
         self.response = response
 
 
+class ContentTypeMismatch(HTTPError):
+    """
+    The response content does not match its declared Content-Type, such as an
+    executable file served as an image.
+
+    May be raised when reading the response, if the client was created using
+    `content_sniffing="raise"`.
+    """
+
+    def __init__(
+        self,
+        message: str,
+        *,
+        request: typing.Optional["Request"],
+        response: "Response",
+    ) -> None:
+        super().__init__(message)
+        self.request = request
+        self.response = response
+
+
 class InvalidURL(Exception):
     """
     URL is improperly formed or cannot be parsed.
+
+    Where a likely correction can be found, such as percent-encoding a space,
+    it is included as the `suggestion` attribute.
+    """
+
+    def __init__(self, message: str, *, suggestion: str = None) -> None:
+        super().__init__(message)
+        self.suggestion = suggestion
+
+
+class QueryParamsTooLarge(Exception):
+    """
+    A query string has more parameters, or is longer, than the configured
+    `max_params` and `max_length` or `max_query_length` limits allow.
     """
 
     def __init__(self, message: str) -> None:
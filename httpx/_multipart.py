@@ -1,13 +1,14 @@
 import binascii
-import os
 import typing
 from pathlib import Path
 
+from ._clock import random_bytes
 from ._transports.base import AsyncByteStream, SyncByteStream
 from ._types import FileContent, FileTypes, RequestFiles
 from ._utils import (
     format_form_param,
     guess_content_type,
+    iter_file,
     peek_filelike_length,
     primitive_value_to_str,
     to_bytes,
@@ -65,8 +66,9 @@ class FileField:
     A single file field item, within a multipart form field.
     """
 
-    def __init__(self, name: str, value: FileTypes) -> None:
+    def __init__(self, name: str, value: FileTypes, use_mmap: bool = False) -> None:
         self.name = name
+        self.use_mmap = use_mmap
 
         fileobj: FileContent
 
@@ -133,7 +135,7 @@ class FileField:
             self.file.seek(0)
         self._consumed = True
 
-        for chunk in self.file:
+        for chunk in iter_file(self.file, use_mmap=self.use_mmap):
             yield to_bytes(chunk)
 
     def render(self) -> typing.Iterator[bytes]:
@@ -146,18 +148,24 @@ class MultipartStream(SyncByteStream, AsyncByteStream):
     Request content as streaming multipart encoded form data.
     """
 
-    def __init__(self, data: dict, files: RequestFiles, boundary: bytes = None) -> None:
+    def __init__(
+        self,
+        data: dict,
+        files: RequestFiles,
+        boundary: bytes = None,
+        use_mmap: bool = False,
+    ) -> None:
         if boundary is None:
-            boundary = binascii.hexlify(os.urandom(16))
+            boundary = binascii.hexlify(random_bytes(16))
 
         self.boundary = boundary
         self.content_type = "multipart/form-data; boundary=%s" % boundary.decode(
             "ascii"
         )
-        self.fields = list(self._iter_fields(data, files))
+        self.fields = list(self._iter_fields(data, files, use_mmap))
 
     def _iter_fields(
-        self, data: dict, files: RequestFiles
+        self, data: dict, files: RequestFiles, use_mmap: bool
     ) -> typing.Iterator[typing.Union[FileField, DataField]]:
         for name, value in data.items():
             if isinstance(value, list):
@@ -168,7 +176,7 @@ class MultipartStream(SyncByteStream, AsyncByteStream):
 
         file_items = files.items() if isinstance(files, typing.Mapping) else files
         for name, value in file_items:
-            yield FileField(name=name, value=value)
+            yield FileField(name=name, value=value, use_mmap=use_mmap)
 
     def iter_chunks(self) -> typing.Iterator[bytes]:
         for field in self.fields:
@@ -0,0 +1,196 @@
+"""
+NTLM message encoding, as used by `NTLMAuth`.
+
+Only NTLMv2 responses are supported. See the NTLM specification for details of
+the message formats: https://docs.microsoft.com/openspecs/windows_protocols/ms-nlmp
+"""
+import hmac
+import struct
+import typing
+
+from ._clock import now, random_bytes
+
+SIGNATURE = b"NTLMSSP\x00"
+
+NEGOTIATE_UNICODE = 0x00000001
+REQUEST_TARGET = 0x00000004
+NEGOTIATE_NTLM = 0x00000200
+NEGOTIATE_ALWAYS_SIGN = 0x00008000
+NEGOTIATE_EXTENDED_SESSIONSECURITY = 0x00080000
+NEGOTIATE_128 = 0x20000000
+NEGOTIATE_56 = 0x80000000
+
+NEGOTIATE_FLAGS = (
+    NEGOTIATE_UNICODE
+    | REQUEST_TARGET
+    | NEGOTIATE_NTLM
+    | NEGOTIATE_ALWAYS_SIGN
+    | NEGOTIATE_EXTENDED_SESSIONSECURITY
+    | NEGOTIATE_128
+    | NEGOTIATE_56
+)
+
+# The target info attribute ID for the server timestamp.
+MSV_AV_TIMESTAMP = 7
+
+# The number of 100-nanosecond intervals between 1601-01-01 and 1970-01-01.
+FILETIME_EPOCH = 116444736000000000
+
+
+class NTLMChallenge(typing.NamedTuple):
+    flags: int
+    server_challenge: bytes
+    target_info: bytes
+
+
+def md4(data: bytes) -> bytes:
+    """
+    Return the MD4 digest of `data`.
+
+    MD4 is required for the NT password hash, but is often unavailable from
+    `hashlib`, since OpenSSL 3 no longer provides it by default.
+    """
+
+    def rotate(x: int, n: int) -> int:
+        return ((x << n) | (x >> (32 - n))) & 0xFFFFFFFF
+
+    message = data + b"\x80" + b"\x00" * ((55 - len(data)) % 64)
+    message += struct.pack("<Q", len(data) * 8)
+
+    state = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476]
+    for offset in range(0, len(message), 64):
+        x = struct.unpack("<16I", message[offset : offset + 64])
+        a, b, c, d = state
+
+        for i in range(16):
+            k, s = i, (3, 7, 11, 19)[i % 4]
+            f = (b & c) | (~b & d)
+            a, b, c, d = d, rotate((a + f + x[k]) & 0xFFFFFFFF, s), b, c
+
+        for i in range(16):
+            k, s = (i % 4) * 4 + i // 4, (3, 5, 9, 13)[i % 4]
+            g = (b & c) | (b & d) | (c & d)
+            a, b, c, d = d, rotate((a + g + x[k] + 0x5A827999) & 0xFFFFFFFF, s), b, c
+
+        for i in range(16):
+            k = (0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15)[i]
+            s = (3, 9, 11, 15)[i % 4]
+            h = b ^ c ^ d
+            a, b, c, d = d, rotate((a + h + x[k] + 0x6ED9EBA1) & 0xFFFFFFFF, s), b, c
+
+        state = [(v + w) & 0xFFFFFFFF for v, w in zip(state, (a, b, c, d))]
+
+    return struct.pack("<4I", *state)
+
+
+def nt_hash(password: str) -> bytes:
+    return md4(password.encode("utf-16-le"))
+
+
+def ntowfv2(username: str, password: str, domain: str) -> bytes:
+    identity = (username.upper() + domain).encode("utf-16-le")
+    return hmac.new(nt_hash(password), identity, "md5").digest()
+
+
+def negotiate_message() -> bytes:
+    """
+    Return a type 1 (negotiate) message.
+    """
+    empty = struct.pack("<HHI", 0, 0, 0)
+    return SIGNATURE + struct.pack("<II", 1, NEGOTIATE_FLAGS) + empty + empty
+
+
+def parse_challenge_message(message: bytes) -> NTLMChallenge:
+    """
+    Parse a type 2 (challenge) message, raising `ValueError` if it is malformed.
+    """
+    if len(message) < 32 or message[:8] != SIGNATURE:
+        raise ValueError("Invalid NTLM challenge message")
+    (message_type,) = struct.unpack("<I", message[8:12])
+    if message_type != 2:
+        raise ValueError(f"Expected NTLM challenge message, got type {message_type}")
+
+    (flags,) = struct.unpack("<I", message[20:24])
+    server_challenge = message[24:32]
+    target_info = b""
+    if len(message) >= 48:
+        length, _, offset = struct.unpack("<HHI", message[40:48])
+        target_info = message[offset : offset + length]
+        if len(target_info) != length:
+            raise ValueError("Invalid NTLM challenge message")
+
+    return NTLMChallenge(
+        flags=flags, server_challenge=server_challenge, target_info=target_info
+    )
+
+
+def get_timestamp(target_info: bytes) -> typing.Optional[bytes]:
+    """
+    Return the server timestamp from the challenge target info, if it is included.
+    """
+    offset = 0
+    while offset + 4 <= len(target_info):
+        av_id, length = struct.unpack("<HH", target_info[offset : offset + 4])
+        if av_id == MSV_AV_TIMESTAMP:
+            return target_info[offset + 4 : offset + 4 + length]
+        elif av_id == 0:
+            break
+        offset += 4 + length
+    return None
+
+
+def authenticate_message(
+    challenge: NTLMChallenge,
+    username: str,
+    password: str,
+    domain: str = "",
+    workstation: str = "",
+    client_challenge: bytes = None,
+    timestamp: bytes = None,
+) -> bytes:
+    """
+    Return a type 3 (authenticate) message, in response to the given challenge.
+    """
+    if client_challenge is None:
+        client_challenge = random_bytes(8)
+
+    server_timestamp = get_timestamp(challenge.target_info)
+    if timestamp is None:
+        timestamp = server_timestamp
+    if timestamp is None:
+        timestamp = struct.pack("<Q", FILETIME_EPOCH + int(now() * 10000000))
+
+    key = ntowfv2(username, password, domain)
+    blob = (
+        b"\x01\x01\x00\x00\x00\x00\x00\x00"
+        + timestamp
+        + client_challenge
+        + b"\x00\x00\x00\x00"
+        + challenge.target_info
+        + b"\x00\x00\x00\x00"
+    )
+    nt_proof = hmac.new(key, challenge.server_challenge + blob, "md5").digest()
+    nt_response = nt_proof + blob
+    if server_timestamp is not None:
+        # If the server provides a timestamp, then the LMv2 response is not sent.
+        lm_response = b"\x00" * 24
+    else:
+        lm_challenge = challenge.server_challenge + client_challenge
+        lm_response = hmac.new(key, lm_challenge, "md5").digest() + client_challenge
+
+    fields = [
+        lm_response,
+        nt_response,
+        domain.encode("utf-16-le"),
+        username.encode("utf-16-le"),
+        workstation.encode("utf-16-le"),
+        b"",  # The encrypted random session key, which is not used.
+    ]
+    header = SIGNATURE + struct.pack("<I", 3)
+    payload = b""
+    offset = len(header) + 8 * len(fields) + 4
+    for field in fields:
+        header += struct.pack("<HHI", len(field), len(field), offset + len(payload))
+        payload += field
+    header += struct.pack("<I", challenge.flags & NEGOTIATE_FLAGS)
+    return header + payload
@@ -0,0 +1,403 @@
+"""
+Support for the Robots Exclusion Protocol, as specified by RFC 9309.
+
+See https://www.rfc-editor.org/rfc/rfc9309.html
+
+Example usage...
+
+# Check a robots.txt file directly.
+robots_txt = httpx.RobotsTxt("User-agent: *\\nDisallow: /private/")
+assert not robots_txt.is_allowed("https://example.org/private/", "MyBot/1.0")
+
+# Fetch and cache robots.txt files using a client.
+client = httpx.Client(headers={"User-Agent": "MyBot/1.0"})
+robots = httpx.Robots(client)
+if robots.is_allowed("https://example.org/page"):
+    response = client.get("https://example.org/page")
+"""
+import re
+import threading
+import typing
+
+from ._client import AsyncClient, Client
+from ._clock import monotonic
+from ._exceptions import HTTPError, TooManyRedirects
+from ._models import URL
+from ._status_codes import codes
+from ._types import URLTypes
+from ._utils import port_or_default, requote
+
+# Crawlers must parse at least the first 500 KiB of a robots.txt file.
+ROBOTS_TXT_MAX_SIZE = 500 * 1024
+
+# Crawlers should not use a cached robots.txt file for more than 24 hours.
+ROBOTS_TXT_MAX_AGE = 24 * 60 * 60
+
+_PATH_SAFE = "!$&'()*+,;=:@/?"
+_PERCENT_ENCODED_RE = re.compile(r"%([0-9A-Fa-f]{2})")
+_UNRESERVED = frozenset(
+    "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-._~"
+)
+_PRODUCT_TOKEN_RE = re.compile(r"[A-Za-z_\-]+")
+
+
+def normalize_robots_path(path: str) -> str:
+    """
+    Normalize the percent-encoding of a URL path or robots.txt path pattern,
+    so that equivalent paths compare equal.
+
+    normalize_robots_path("/a b/%7euser/%2f") == "/a%20b/~user/%2F"
+    """
+
+    def normalize_escape(match: typing.Match[str]) -> str:
+        char = chr(int(match.group(1), 16))
+        return char if char in _UNRESERVED else match.group(0).upper()
+
+    path = requote(path, safe=_PATH_SAFE)
+    return _PERCENT_ENCODED_RE.sub(normalize_escape, path)
+
+
+def product_token(user_agent: str) -> str:
+    """
+    Return the product token from a 'User-Agent' header, as used to match
+    against robots.txt groups.
+
+    product_token("ExampleBot/1.0 (+https://example.org/bot)") == "ExampleBot"
+    """
+    match = _PRODUCT_TOKEN_RE.match(user_agent.strip())
+    return "" if match is None else match.group(0)
+
+
+class RobotsRule:
+    """
+    A single 'Allow' or 'Disallow' rule from a robots.txt file.
+    """
+
+    def __init__(self, pattern: str, allow: bool) -> None:
+        self.pattern = normalize_robots_path(pattern)
+        self.allow = allow
+
+        # Patterns may include '*' to match any sequence of characters,
+        # and a trailing '$' to match the end of the path.
+        anchored = self.pattern.endswith("$")
+        pattern = self.pattern[:-1] if anchored else self.pattern
+        regex = ".*".join(re.escape(part) for part in pattern.split("*"))
+        self._regex = re.compile(regex + ("$" if anchored else ""), re.DOTALL)
+
+    def matches(self, path: str) -> bool:
+        return self._regex.match(path) is not None
+
+    def __repr__(self) -> str:
+        directive = "Allow" if self.allow else "Disallow"
+        return f"<{self.__class__.__name__} {directive}: {self.pattern}>"
+
+
+class RobotsGroup:
+    """
+    A group of rules from a robots.txt file, and the user agents they apply to.
+    """
+
+    def __init__(self, user_agents: typing.List[str]) -> None:
+        self.user_agents = user_agents
+        self.rules: typing.List[RobotsRule] = []
+        self.crawl_delay: typing.Optional[float] = None
+
+
+class RobotsTxt:
+    """
+    A parsed robots.txt file.
+
+    Usage:
+
+    robots_txt = httpx.RobotsTxt(response.text)
+    if robots_txt.is_allowed("https://example.org/page", "ExampleBot/1.0"):
+        ...
+
+    Groups are matched using the product token of the given user agent, such
+    as "ExampleBot", case-insensitively. Where no group matches, the rules for
+    the `*` user agent apply. Where several rules match a URL, the rule with
+    the longest path pattern is used, and `Allow` rules take precedence over
+    `Disallow` rules of the same length.
+    """
+
+    def __init__(self, content: str = "") -> None:
+        self.groups: typing.List[RobotsGroup] = []
+        self.sitemaps: typing.List[str] = []
+
+        group: typing.Optional[RobotsGroup] = None
+        in_user_agents = False
+        for line in content.splitlines():
+            line = line.split("#", 1)[0].strip()
+            key, colon, value = line.partition(":")
+            if not colon:
+                continue
+            key = key.strip().lower()
+            value = value.strip()
+
+            if key == "user-agent":
+                if group is None or not in_user_agents:
+                    group = RobotsGroup([])
+                    self.groups.append(group)
+                group.user_agents.append(value.lower())
+                in_user_agents = True
+                continue
+
+            in_user_agents = False
+            if key == "sitemap":
+                self.sitemaps.append(value)
+            elif group is None:
+                # Rules that appear before any 'User-agent' line are ignored.
+                continue
+            elif key in ("allow", "disallow") and value:
+                group.rules.append(RobotsRule(value, allow=key == "allow"))
+            elif key == "crawl-delay":
+                try:
+                    group.crawl_delay = float(value)
+                except ValueError:
+                    pass
+
+    @classmethod
+    def allow_all(cls) -> "RobotsTxt":
+        """
+        Return a robots.txt that allows all URLs, as used when the file is
+        unavailable.
+        """
+        return cls()
+
+    @classmethod
+    def disallow_all(cls) -> "RobotsTxt":
+        """
+        Return a robots.txt that disallows all URLs, as used when the file is
+        unreachable.
+        """
+        return cls("User-agent: *\nDisallow: /")
+
+    def _groups_for(self, user_agent: str) -> typing.List[RobotsGroup]:
+        token = product_token(user_agent).lower()
+        groups = [group for group in self.groups if token in group.user_agents]
+        if not groups:
+            groups = [group for group in self.groups if "*" in group.user_agents]
+        return groups
+
+    def is_allowed(self, url: URLTypes, user_agent: str) -> bool:
+        """
+        Return `True` if the given user agent may crawl the URL.
+        """
+        path = normalize_robots_path(URL(url).raw_path.decode("ascii"))
+        if path == "/robots.txt":
+            return True
+
+        matched: typing.Optional[RobotsRule] = None
+        for group in self._groups_for(user_agent):
+            for rule in group.rules:
+                if not rule.matches(path):
+                    continue
+                if (
+                    matched is None
+                    or len(rule.pattern) > len(matched.pattern)
+                    or (len(rule.pattern) == len(matched.pattern) and rule.allow)
+                ):
+                    matched = rule
+        return matched is None or matched.allow
+
+    def crawl_delay(self, user_agent: str) -> typing.Optional[float]:
+        """
+        Return the 'Crawl-delay' for the given user agent, in seconds, or `None`
+        if not set. This is not part of RFC 9309, but is commonly used.
+        """
+        for group in self._groups_for(user_agent):
+            if group.crawl_delay is not None:
+                return group.crawl_delay
+        return None
+
+
+class BaseRobots:
+    def __init__(self, max_age: float = ROBOTS_TXT_MAX_AGE) -> None:
+        self.max_age = max_age
+        self._cache: typing.Dict[
+            typing.Tuple[str, str, typing.Optional[int]],
+            typing.Tuple[RobotsTxt, float],
+        ] = {}
+        self._lock = threading.Lock()
+
+    def _cache_key(self, url: URL) -> typing.Tuple[str, str, typing.Optional[int]]:
+        return (url.scheme, url.host, port_or_default(url))
+
+    def _get_cached(self, url: URL) -> typing.Optional[RobotsTxt]:
+        with self._lock:
+            cached = self._cache.get(self._cache_key(url))
+        if cached is not None and cached[1] > monotonic():
+            return cached[0]
+        return None
+
+    def _set_cached(self, url: URL, robots_txt: RobotsTxt) -> None:
+        with self._lock:
+            expires = monotonic() + self.max_age
+            self._cache[self._cache_key(url)] = (robots_txt, expires)
+
+    def _robots_txt_url(self, url: URL) -> URL:
+        return url.copy_with(userinfo=b"", raw_path=b"/robots.txt", fragment=None)
+
+    def _robots_txt_for_status(self, status_code: int) -> RobotsTxt:
+        # A 5xx response means that the server is unreachable, and no URL may
+        # be crawled. Otherwise the robots.txt file is unavailable, for example
+        # with a 4xx response, and any URL may be crawled.
+        if codes.is_server_error(status_code):
+            return RobotsTxt.disallow_all()
+        return RobotsTxt.allow_all()
+
+    def clear(self) -> None:
+        """
+        Remove all cached robots.txt files.
+        """
+        with self._lock:
+            self._cache.clear()
+
+
+class Robots(BaseRobots):
+    """
+    Fetches and caches robots.txt files for each origin, using a client.
+
+    Usage:
+
+    client = httpx.Client(headers={"User-Agent": "ExampleBot/1.0"})
+    robots = httpx.Robots(client)
+    if robots.is_allowed("https://example.org/page"):
+        ...
+
+    URLs are merged with any `base_url` set on the client, and the client's
+    'User-Agent' header is used unless a `user_agent` is given.
+
+    **Parameters:**
+
+    * **client** - The client used to fetch robots.txt files.
+    * **max_age** - *(optional)* The time in seconds to cache each robots.txt
+    file for. Defaults to 24 hours.
+    """
+
+    def __init__(self, client: Client, max_age: float = ROBOTS_TXT_MAX_AGE) -> None:
+        super().__init__(max_age=max_age)
+        self.client = client
+
+    def get_robots_txt(self, url: URLTypes) -> RobotsTxt:
+        """
+        Return the robots.txt file that applies to the given URL, fetching it
+        if it is not already cached.
+        """
+        url = self.client._merge_url(url)
+        robots_txt = self._get_cached(url)
+        if robots_txt is None:
+            robots_txt = self._fetch(self._robots_txt_url(url))
+            self._set_cached(url, robots_txt)
+        return robots_txt
+
+    def _fetch(self, url: URL) -> RobotsTxt:
+        try:
+            with self.client.stream("GET", url) as response:
+                if response.status_code >= 300:
+                    return self._robots_txt_for_status(response.status_code)
+                content = b""
+                for chunk in response.iter_bytes():
+                    content += chunk
+                    if len(content) >= ROBOTS_TXT_MAX_SIZE:
+                        break
+        except TooManyRedirects:
+            return RobotsTxt.allow_all()
+        except HTTPError:
+            return RobotsTxt.disallow_all()
+        text = content[:ROBOTS_TXT_MAX_SIZE].decode("utf-8", errors="replace")
+        return RobotsTxt(text)
+
+    def is_allowed(self, url: URLTypes, user_agent: str = None) -> bool:
+        """
+        Return `True` if the URL may be crawled.
+        """
+        if user_agent is None:
+            user_agent = self.client.headers.get("User-Agent", "")
+        url = self.client._merge_url(url)
+        return self.get_robots_txt(url).is_allowed(url, user_agent)
+
+    def crawl_delay(
+        self, url: URLTypes, user_agent: str = None
+    ) -> typing.Optional[float]:
+        """
+        Return the 'Crawl-delay' in seconds that applies to the URL, or `None`.
+        """
+        if user_agent is None:
+            user_agent = self.client.headers.get("User-Agent", "")
+        return self.get_robots_txt(url).crawl_delay(user_agent)
+
+
+class AsyncRobots(BaseRobots):
+    """
+    Fetches and caches robots.txt files for each origin, using an async client.
+
+    Usage:
+
+    client = httpx.AsyncClient(headers={"User-Agent": "ExampleBot/1.0"})
+    robots = httpx.AsyncRobots(client)
+    if await robots.is_allowed("https://example.org/page"):
+        ...
+
+    **Parameters:**
+
+    * **client** - The client used to fetch robots.txt files.
+    * **max_age** - *(optional)* The time in seconds to cache each robots.txt
+    file for. Defaults to 24 hours.
+    """
+
+    def __init__(
+        self, client: AsyncClient, max_age: float = ROBOTS_TXT_MAX_AGE
+    ) -> None:
+        super().__init__(max_age=max_age)
+        self.client = client
+
+    async def get_robots_txt(self, url: URLTypes) -> RobotsTxt:
+        """
+        Return the robots.txt file that applies to the given URL, fetching it
+        if it is not already cached.
+        """
+        url = self.client._merge_url(url)
+        robots_txt = self._get_cached(url)
+        if robots_txt is None:
+            robots_txt = await self._fetch(self._robots_txt_url(url))
+            self._set_cached(url, robots_txt)
+        return robots_txt
+
+    async def _fetch(self, url: URL) -> RobotsTxt:
+        try:
+            async with self.client.stream("GET", url) as response:
+                if response.status_code >= 300:
+                    return self._robots_txt_for_status(response.status_code)
+                content = b""
+                async for chunk in response.aiter_bytes():
+                    content += chunk
+                    if len(content) >= ROBOTS_TXT_MAX_SIZE:
+                        break
+        except TooManyRedirects:
+            return RobotsTxt.allow_all()
+        except HTTPError:
+            return RobotsTxt.disallow_all()
+        text = content[:ROBOTS_TXT_MAX_SIZE].decode("utf-8", errors="replace")
+        return RobotsTxt(text)
+
+    async def is_allowed(self, url: URLTypes, user_agent: str = None) -> bool:
+        """
+        Return `True` if the URL may be crawled.
+        """
+        if user_agent is None:
+            user_agent = self.client.headers.get("User-Agent", "")
+        url = self.client._merge_url(url)
+        robots_txt = await self.get_robots_txt(url)
+        return robots_txt.is_allowed(url, user_agent)
+
+    async def crawl_delay(
+        self, url: URLTypes, user_agent: str = None
+    ) -> typing.Optional[float]:
+        """
+        Return the 'Crawl-delay' in seconds that applies to the URL, or `None`.
+        """
+        if user_agent is None:
+            user_agent = self.client.headers.get("User-Agent", "")
+        robots_txt = await self.get_robots_txt(url)
+        return robots_txt.crawl_delay(user_agent)
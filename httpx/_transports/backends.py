@@ -0,0 +1,219 @@
+"""
+Network backends used by the transports for the `local_port`, `reuse_address`,
+`resolver`, and `read_size` options.
+
+These subclass httpcore's private backend classes, so this module is only
+imported once one of those options is used, rather than along with `httpx`.
+"""
+import socket
+import typing
+from ssl import SSLContext
+
+import httpcore
+from httpcore._backends.base import AsyncBackend, AsyncSocketStream, lookup_async_backend
+from httpcore._backends.sync import SyncBackend, SyncSocketStream
+
+if typing.TYPE_CHECKING:  # pragma: no cover
+    from .doh import DoHResolver
+
+
+class SocketOptionsBackend(SyncBackend):
+    """
+    A sync network backend that provides extra control over how outgoing
+    TCP connections are established.
+
+    * `local_port` - Bind connections to an explicit source port. Only a single
+      connection to a given remote address may use a given source port at any
+      one time, so this is typically combined with `Limits(max_connections=1)`.
+    * `reuse_address` - Set `SO_REUSEADDR`, so that the source port may be rebound
+      without waiting for previous connections to leave the `TIME_WAIT` state.
+    * `resolver` - Resolve hostnames using the given resolver, such as a
+      `DoHResolver`, rather than the system resolver.
+    * `read_size` - The maximum number of bytes to read from a socket at once,
+      rather than the fixed size used by httpcore.
+    """
+
+    def __init__(
+        self,
+        local_port: int = None,
+        reuse_address: bool = False,
+        resolver: "DoHResolver" = None,
+        read_size: int = None,
+    ) -> None:
+        self._local_port = local_port
+        self._reuse_address = reuse_address
+        self._resolver = resolver
+        self._read_size = read_size
+
+    def open_tcp_stream(
+        self,
+        hostname: bytes,
+        port: int,
+        ssl_context: typing.Optional[SSLContext],
+        timeout: typing.Dict[str, typing.Optional[float]],
+        *,
+        local_address: typing.Optional[str],
+    ) -> SyncSocketStream:
+        host = hostname.decode("ascii")
+        source_address = (local_address or "", self._local_port or 0)
+        connect_timeout = timeout.get("connect")
+
+        try:
+            sock = self._create_connection(
+                (host, port), source_address, timeout=connect_timeout
+            )
+            if ssl_context is not None:
+                sock = ssl_context.wrap_socket(sock, server_hostname=host)
+        except socket.timeout as exc:
+            raise httpcore.ConnectTimeout(exc) from exc
+        except OSError as exc:
+            raise httpcore.ConnectError(exc) from exc
+
+        if self._read_size is not None:
+            return SizedSocketStream(sock=sock, read_size=self._read_size)
+        return SyncSocketStream(sock=sock)
+
+    def _create_connection(
+        self,
+        address: typing.Tuple[str, int],
+        source_address: typing.Tuple[str, int],
+        timeout: typing.Optional[float],
+    ) -> socket.socket:
+        """
+        Equivalent to `socket.create_connection()`, except that socket options
+        are applied before the socket is bound to its source address, and that
+        hostnames may be resolved using a custom resolver.
+        """
+        host, port = address
+        hosts = [host] if self._resolver is None else self._resolver.resolve(host)
+
+        error: typing.Optional[OSError] = None
+        for host in hosts:
+            for family, type_, proto, _, sockaddr in socket.getaddrinfo(
+                host, port, 0, socket.SOCK_STREAM
+            ):
+                sock = socket.socket(family, type_, proto)
+                try:
+                    if self._reuse_address:
+                        sock.setsockopt(socket.SOL_SOCKET, socket.SO_REUSEADDR, 1)
+                    sock.settimeout(timeout)
+                    sock.bind(source_address)
+                    sock.connect(sockaddr)
+                    return sock
+                except OSError as exc:
+                    error = exc
+                    sock.close()
+
+        if error is None:  # pragma: nocover
+            error = OSError(f"getaddrinfo returned no results for {address[0]!r}")
+        raise error
+
+
+class SizedSocketStream(SyncSocketStream):
+    """
+    A socket stream that reads up to `read_size` bytes at a time, regardless of
+    the number of bytes requested.
+    """
+
+    def __init__(self, sock: socket.socket, read_size: int) -> None:
+        super().__init__(sock=sock)
+        self._read_size = read_size
+
+    def start_tls(
+        self,
+        hostname: bytes,
+        ssl_context: SSLContext,
+        timeout: typing.Dict[str, typing.Optional[float]],
+    ) -> SyncSocketStream:
+        stream = super().start_tls(hostname, ssl_context, timeout)
+        return SizedSocketStream(sock=stream.sock, read_size=self._read_size)
+
+    def read(self, n: int, timeout: typing.Dict[str, typing.Optional[float]]) -> bytes:
+        return super().read(self._read_size, timeout)
+
+
+class SizedReadBackend(AsyncBackend):
+    """
+    An async network backend whose socket streams read up to `read_size` bytes
+    at a time, regardless of the number of bytes requested.
+    """
+
+    def __init__(self, backend: typing.Union[str, AsyncBackend], read_size: int):
+        self._backend = lookup_async_backend(backend)
+        self._read_size = read_size
+
+    async def open_tcp_stream(
+        self,
+        hostname: bytes,
+        port: int,
+        ssl_context: typing.Optional[SSLContext],
+        timeout: typing.Dict[str, typing.Optional[float]],
+        *,
+        local_address: typing.Optional[str],
+    ) -> AsyncSocketStream:
+        stream = await self._backend.open_tcp_stream(
+            hostname, port, ssl_context, timeout, local_address=local_address
+        )
+        return SizedAsyncSocketStream(stream, read_size=self._read_size)
+
+    async def open_uds_stream(
+        self,
+        path: str,
+        hostname: bytes,
+        ssl_context: typing.Optional[SSLContext],
+        timeout: typing.Dict[str, typing.Optional[float]],
+    ) -> AsyncSocketStream:
+        stream = await self._backend.open_uds_stream(
+            path, hostname, ssl_context, timeout
+        )
+        return SizedAsyncSocketStream(stream, read_size=self._read_size)
+
+    def create_lock(self) -> typing.Any:
+        return self._backend.create_lock()
+
+    def create_semaphore(self, max_value: int, exc_class: type) -> typing.Any:
+        return self._backend.create_semaphore(max_value, exc_class=exc_class)
+
+    async def time(self) -> float:
+        return await self._backend.time()
+
+    async def sleep(self, seconds: float) -> None:
+        await self._backend.sleep(seconds)
+
+
+class SizedAsyncSocketStream(AsyncSocketStream):
+    """
+    Wraps an async socket stream, reading up to `read_size` bytes at a time.
+    """
+
+    def __init__(self, stream: AsyncSocketStream, read_size: int) -> None:
+        self._stream = stream
+        self._read_size = read_size
+
+    def get_http_version(self) -> str:
+        return self._stream.get_http_version()
+
+    async def start_tls(
+        self,
+        hostname: bytes,
+        ssl_context: SSLContext,
+        timeout: typing.Dict[str, typing.Optional[float]],
+    ) -> AsyncSocketStream:
+        stream = await self._stream.start_tls(hostname, ssl_context, timeout)
+        return SizedAsyncSocketStream(stream, read_size=self._read_size)
+
+    async def read(
+        self, n: int, timeout: typing.Dict[str, typing.Optional[float]]
+    ) -> bytes:
+        return await self._stream.read(self._read_size, timeout)
+
+    async def write(
+        self, data: bytes, timeout: typing.Dict[str, typing.Optional[float]]
+    ) -> None:
+        await self._stream.write(data, timeout)
+
+    async def aclose(self) -> None:
+        await self._stream.aclose()
+
+    def is_readable(self) -> bool:
+        return self._stream.is_readable()
@@ -149,6 +149,27 @@ class BaseTransport:
             "The 'handle_request' method must be implemented."
         )  # pragma: nocover
 
+    def preconnect(
+        self,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        count: int = 1,
+        extensions: dict = None,
+    ) -> None:
+        """
+        Establish connections to the origin of the given URL ahead of time, so
+        that subsequent requests are able to skip connection setup.
+
+        Arguments:
+
+        url: The components of a URL, as a tuple of `(scheme, host, port, target)`.
+             Only the `(scheme, host, port)` origin portion is used.
+        count: The number of connections to establish.
+        extensions: An open ended dictionary, as with `handle_request`. Keys may
+                    include 'timeout'.
+
+        Transports that do not maintain a connection pool may leave this as a no-op.
+        """
+
     def close(self) -> None:
         pass
 
@@ -179,5 +200,13 @@ class AsyncBaseTransport:
             "The 'handle_async_request' method must be implemented."
         )  # pragma: nocover
 
+    async def apreconnect(
+        self,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        count: int = 1,
+        extensions: dict = None,
+    ) -> None:
+        pass
+
     async def aclose(self) -> None:
         pass
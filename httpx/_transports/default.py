@@ -8,6 +8,17 @@ The following additional keyword arguments are currently supported by httpcore..
 * retries: int
 * backend: str ("auto", "asyncio", "trio", "curio", "anyio", "sync")
 
+The sync transport additionally supports some lower-level socket controls...
+
+* local_port: int
+* reuse_address: bool
+* resolver: DoHResolver
+
+Both transports support controlling the size of reads and response chunks...
+
+* read_size: int
+* min_chunk_size: int
+
 Example usages...
 
 # Disable HTTP/2 on a single specfic domain.
@@ -23,13 +34,38 @@ client = httpx.Client(transport=transport)
 # Using advanced httpcore configuration, with unix domain sockets.
 transport = httpx.HTTPTransport(uds="socket.uds")
 client = httpx.Client(transport=transport)
+
+# Always connect from a fixed source port, allowing the port to be rebound
+# immediately once a previous connection has closed.
+transport = httpx.HTTPTransport(local_port=40000, reuse_address=True)
+client = httpx.Client(transport=transport)
+
+# Read from the network in larger chunks, and coalesce response content into
+# chunks of at least 1MB, for more efficient large downloads.
+transport = httpx.HTTPTransport(read_size=256 * 1024, min_chunk_size=1024 * 1024)
+client = httpx.Client(transport=transport)
+
+# Send headers in a fixed order, and with the given casing. Any other headers
+# are sent afterwards.
+header_order = ["Host", "User-Agent", "Accept", "Accept-Encoding", "Connection"]
+transport = httpx.HTTPTransport(header_order=header_order)
+client = httpx.Client(transport=transport)
+
+# Fail immediately for 30 seconds after a host cannot be resolved or connected to,
+# rather than waiting on DNS and connect timeouts for every request.
+transport = httpx.HTTPTransport(failure_cache_ttl=30.0)
+client = httpx.Client(transport=transport)
+transport.clear_failure_cache("https://www.example.com")
 """
 import contextlib
+import threading
 import typing
+import warnings
 from types import TracebackType
 
 import httpcore
 
+from .._clock import monotonic
 from .._config import DEFAULT_LIMITS, Limits, Proxy, create_ssl_context
 from .._exceptions import (
     CloseError,
@@ -48,13 +84,107 @@ from .._exceptions import (
     WriteError,
     WriteTimeout,
 )
-from .._types import CertTypes, VerifyTypes
+from .._models import URL
+from .._types import CertTypes, URLTypes, VerifyTypes
 from .base import AsyncBaseTransport, AsyncByteStream, BaseTransport, SyncByteStream
 
+if typing.TYPE_CHECKING:  # pragma: no cover
+    from httpcore._backends.base import AsyncBackend
+    from httpcore._backends.sync import SyncBackend
+
+    from .doh import DoHResolver
+
 T = typing.TypeVar("T", bound="HTTPTransport")
 A = typing.TypeVar("A", bound="AsyncHTTPTransport")
 
 
+# Preconnecting and connection affinity use httpcore internals, which are only
+# stable within the httpcore release series pinned in setup.py. They are checked
+# for up front, so that an incompatible httpcore skips preconnecting with a
+# warning, and sends requests through the pool without affinity, rather than
+# failing part way through establishing a connection.
+HTTPCORE_POOL_INTERNALS = (
+    "_add_to_pool",
+    "_remove_from_pool",
+    "_http1",
+    "_http2",
+    "_keepalive_expiry",
+    "_uds",
+    "_ssl_context",
+    "_local_address",
+    "_retries",
+    "_backend",
+)
+HTTPCORE_CONNECTION_INTERNALS = ("request_lock", "_open_socket", "_create_connection")
+
+
+def sync_connection_class() -> typing.Optional[type]:
+    """
+    Return httpcore's sync connection class, or `None` if the installed httpcore
+    does not provide it.
+    """
+    try:
+        from httpcore._sync.connection import SyncHTTPConnection
+    except ImportError:  # pragma: nocover
+        return None
+    return SyncHTTPConnection
+
+
+def async_connection_class() -> typing.Optional[type]:
+    """
+    Return httpcore's async connection class, or `None` if the installed httpcore
+    does not provide it.
+    """
+    try:
+        from httpcore._async.connection import AsyncHTTPConnection
+    except ImportError:  # pragma: nocover
+        return None
+    return AsyncHTTPConnection
+
+
+def can_use_affinity(pool: typing.Any, connection_class: typing.Optional[type]) -> bool:
+    """
+    Return `True` if connections can be created outside of the given pool, and
+    added to it later.
+    """
+    return (
+        not isinstance(pool, (httpcore.SyncHTTPProxy, httpcore.AsyncHTTPProxy))
+        and connection_class is not None
+        and all(hasattr(pool, name) for name in HTTPCORE_POOL_INTERNALS)
+    )
+
+
+def can_preconnect(pool: typing.Any, connection_class: typing.Optional[type]) -> bool:
+    """
+    Return `True` if connections can be preconnected for the given pool, issuing
+    a warning if not.
+    """
+    if isinstance(pool, (httpcore.SyncHTTPProxy, httpcore.AsyncHTTPProxy)):
+        warnings.warn("Connections made through a proxy are not preconnected.")
+        return False
+    if not can_use_affinity(pool, connection_class) or not all(
+        hasattr(connection_class, name) for name in HTTPCORE_CONNECTION_INTERNALS
+    ):
+        warnings.warn("Preconnecting is not supported by the installed httpcore.")
+        return False
+    return True
+
+
+def import_backends() -> typing.Any:
+    """
+    Import the network backends used for the lower-level socket options, which
+    require httpcore internals.
+    """
+    try:
+        from . import backends
+    except ImportError as exc:  # pragma: nocover
+        raise RuntimeError(
+            "The local_port, reuse_address, resolver, and read_size options are "
+            "not supported by the installed httpcore."
+        ) from exc
+    return backends
+
+
 @contextlib.contextmanager
 def map_httpcore_exceptions() -> typing.Iterator[None]:
     try:
@@ -97,14 +227,131 @@ HTTPCORE_EXC_MAP = {
 }
 
 
+def url_to_origin(
+    url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes]
+) -> typing.Tuple[bytes, bytes, int]:
+    """
+    Return the `(scheme, host, port)` origin for a raw URL tuple, filling
+    in the default port if required.
+    """
+    scheme, host, port, _ = url
+    if scheme not in (b"http", b"https"):
+        raise UnsupportedProtocol(f"Unsupported URL protocol {scheme!r}")
+    if port is None:
+        port = {b"http": 80, b"https": 443}[scheme]
+    return scheme, host, port
+
+
+def build_header_order(
+    header_order: typing.Optional[typing.Sequence[str]],
+) -> typing.Dict[bytes, typing.Tuple[int, bytes]]:
+    """
+    Map each lowercased header name to its position in `header_order`, and the
+    casing with which it should be sent.
+    """
+    if header_order is None:
+        return {}
+    return {
+        name.lower().encode("ascii"): (index, name.encode("ascii"))
+        for index, name in enumerate(header_order)
+    }
+
+
+def order_headers(
+    headers: typing.List[typing.Tuple[bytes, bytes]],
+    header_order: typing.Dict[bytes, typing.Tuple[int, bytes]],
+) -> typing.List[typing.Tuple[bytes, bytes]]:
+    """
+    Return the headers sorted into the order given by `build_header_order()`,
+    using the casing given there. Any other headers follow, in their original
+    order, with their original casing. This is applied after any canonical
+    casing from the client's `canonical_header_names`, so it takes precedence.
+    """
+    if not header_order:
+        return headers
+    ordered = []
+    for key, value in headers:
+        index, name = header_order.get(key.lower(), (len(header_order), key))
+        ordered.append((index, name, value))
+    ordered.sort(key=lambda item: item[0])
+    return [(name, value) for _, name, value in ordered]
+
+
+class FailureCache:
+    """
+    Remembers connection failures for each origin, including failures to
+    resolve the hostname, so that further requests to an unreachable origin
+    fail immediately for `ttl` seconds, rather than waiting on DNS resolution
+    and connect timeouts every time. A `ttl` of `None` disables the cache.
+    """
+
+    def __init__(self, ttl: float = None) -> None:
+        self._ttl = ttl
+        self._failures: typing.Dict[
+            typing.Tuple[bytes, bytes, int], typing.Tuple[float, Exception]
+        ] = {}
+        self._lock = threading.Lock()
+
+    @contextlib.contextmanager
+    def track(
+        self, url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes]
+    ) -> typing.Iterator[None]:
+        if self._ttl is None:
+            yield
+            return
+
+        origin = url_to_origin(url)
+        with self._lock:
+            failure = self._failures.get(origin)
+            if failure is not None and failure[0] <= monotonic():
+                del self._failures[origin]
+                failure = None
+        if failure is not None:
+            exc = failure[1]
+            raise type(exc)(f"{exc} (cached connection failure)")
+
+        try:
+            yield
+        except (ConnectError, ConnectTimeout) as exc:
+            with self._lock:
+                self._failures[origin] = (monotonic() + self._ttl, exc)
+            raise
+
+    def clear(self, url: URLTypes = None) -> None:
+        with self._lock:
+            if url is None:
+                self._failures.clear()
+            else:
+                self._failures.pop(url_to_origin(URL(url).raw), None)
+
+
 class ResponseStream(SyncByteStream):
-    def __init__(self, httpcore_stream: httpcore.SyncByteStream):
+    """
+    The response content. With a `min_chunk_size`, smaller parts are coalesced
+    so that every chunk except the last is at least that many bytes.
+    """
+
+    def __init__(
+        self, httpcore_stream: httpcore.SyncByteStream, min_chunk_size: int = None
+    ):
         self._httpcore_stream = httpcore_stream
+        self._min_chunk_size = min_chunk_size
 
     def __iter__(self) -> typing.Iterator[bytes]:
         with map_httpcore_exceptions():
+            if self._min_chunk_size is None:
+                for part in self._httpcore_stream:
+                    yield part
+                return
+
+            buffer = bytearray()
             for part in self._httpcore_stream:
-                yield part
+                buffer.extend(part)
+                if len(buffer) >= self._min_chunk_size:
+                    yield bytes(buffer)
+                    buffer.clear()
+            if buffer:
+                yield bytes(buffer)
 
     def close(self) -> None:
         with map_httpcore_exceptions():
@@ -123,9 +370,32 @@ class HTTPTransport(BaseTransport):
         uds: str = None,
         local_address: str = None,
         retries: int = 0,
-        backend: str = "sync",
+        backend: typing.Union[str, "SyncBackend"] = "sync",
+        local_port: int = None,
+        reuse_address: bool = False,
+        resolver: "DoHResolver" = None,
+        read_size: int = None,
+        min_chunk_size: int = None,
+        failure_cache_ttl: float = None,
+        header_order: typing.Sequence[str] = None,
     ) -> None:
         ssl_context = create_ssl_context(verify=verify, cert=cert, trust_env=trust_env)
+        self._min_chunk_size = min_chunk_size
+        self._failure_cache = FailureCache(ttl=failure_cache_ttl)
+        self._header_order = build_header_order(header_order)
+
+        if (
+            local_port is not None
+            or reuse_address
+            or resolver is not None
+            or read_size is not None
+        ):
+            backend = import_backends().SocketOptionsBackend(
+                local_port=local_port,
+                reuse_address=reuse_address,
+                resolver=resolver,
+                read_size=read_size,
+            )
 
         if proxy is None:
             self._pool = httpcore.SyncConnectionPool(
@@ -175,7 +445,15 @@ class HTTPTransport(BaseTransport):
     ) -> typing.Tuple[
         int, typing.List[typing.Tuple[bytes, bytes]], SyncByteStream, dict
     ]:
-        with map_httpcore_exceptions():
+        headers = order_headers(headers, self._header_order)
+        affinity = extensions.get("connection_affinity")
+        connection_class = sync_connection_class()
+        if affinity is not None and can_use_affinity(self._pool, connection_class):
+            return self._handle_request_with_affinity(
+                affinity, connection_class, method, url, headers, stream, extensions
+            )
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
             status_code, headers, byte_stream, extensions = self._pool.handle_request(
                 method=method,
                 url=url,
@@ -184,22 +462,141 @@ class HTTPTransport(BaseTransport):
                 extensions=extensions,
             )
 
-        stream = ResponseStream(byte_stream)
+        stream = ResponseStream(byte_stream, self._min_chunk_size)
 
         return status_code, headers, stream, extensions
 
+    def _handle_request_with_affinity(
+        self,
+        affinity: dict,
+        connection_class: typing.Any,
+        method: bytes,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        headers: typing.List[typing.Tuple[bytes, bytes]],
+        stream: SyncByteStream,
+        extensions: dict,
+    ) -> typing.Tuple[
+        int, typing.List[typing.Tuple[bytes, bytes]], SyncByteStream, dict
+    ]:
+        """
+        Send a request over the same connection as any previous requests sharing
+        the "connection_affinity" extension, as required by connection-oriented
+        authentication schemes such as NTLM.
+
+        The connection is kept out of the pool until a request is sent with
+        `affinity["release"] = True`, at which point it is added to the pool.
+        If the installed httpcore does not support this, requests are sent
+        through the pool as usual.
+        """
+        origin = url_to_origin(url)
+        timeout = extensions.get("timeout", {})
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
+            connection = affinity.get("connection")
+            if (
+                connection is None
+                or connection.origin != origin
+                or connection.should_close()
+            ):
+                connection = self._new_connection(origin, connection_class)
+                affinity["connection"] = connection
+
+            status_code, headers, byte_stream, extensions = connection.handle_request(
+                method=method,
+                url=url,
+                headers=headers,
+                stream=httpcore.IteratorByteStream(iter(stream)),
+                extensions=extensions,
+            )
+
+            if affinity.get("release"):
+                del affinity["connection"]
+                self._pool._add_to_pool(connection, timeout=timeout)
+
+        stream = ResponseStream(byte_stream, self._min_chunk_size)
+
+        return status_code, headers, stream, extensions
+
+    def _new_connection(
+        self, origin: typing.Tuple[bytes, bytes, int], connection_class: typing.Any
+    ) -> typing.Any:
+        pool = self._pool
+        return connection_class(
+            origin=origin,
+            http1=pool._http1,
+            http2=pool._http2,
+            keepalive_expiry=pool._keepalive_expiry,
+            uds=pool._uds,
+            ssl_context=pool._ssl_context,
+            local_address=pool._local_address,
+            retries=pool._retries,
+            backend=pool._backend,
+        )
+
+    def preconnect(
+        self,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        count: int = 1,
+        extensions: dict = None,
+    ) -> None:
+        connection_class = sync_connection_class()
+        if not can_preconnect(self._pool, connection_class):
+            return
+
+        origin = url_to_origin(url)
+        timeout = {} if extensions is None else extensions.get("timeout", {})
+        pool = self._pool
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
+            for _ in range(count):
+                connection = self._new_connection(origin, connection_class)
+                pool._add_to_pool(connection, timeout=timeout)
+                try:
+                    with connection.request_lock:
+                        connection.socket = connection._open_socket(timeout)
+                        connection._create_connection(connection.socket)
+                except BaseException:
+                    pool._remove_from_pool(connection)
+                    raise
+
+    def clear_failure_cache(self, url: URLTypes = None) -> None:
+        """
+        Forget any cached connection failure for the origin of the given URL,
+        or for all origins if no URL is given.
+        """
+        self._failure_cache.clear(url)
+
     def close(self) -> None:
         self._pool.close()
 
 
 class AsyncResponseStream(AsyncByteStream):
-    def __init__(self, httpcore_stream: httpcore.AsyncByteStream):
+    """
+    The response content. With a `min_chunk_size`, smaller parts are coalesced
+    so that every chunk except the last is at least that many bytes.
+    """
+
+    def __init__(
+        self, httpcore_stream: httpcore.AsyncByteStream, min_chunk_size: int = None
+    ):
         self._httpcore_stream = httpcore_stream
+        self._min_chunk_size = min_chunk_size
 
     async def __aiter__(self) -> typing.AsyncIterator[bytes]:
         with map_httpcore_exceptions():
+            if self._min_chunk_size is None:
+                async for part in self._httpcore_stream:
+                    yield part
+                return
+
+            buffer = bytearray()
             async for part in self._httpcore_stream:
-                yield part
+                buffer.extend(part)
+                if len(buffer) >= self._min_chunk_size:
+                    yield bytes(buffer)
+                    buffer.clear()
+            if buffer:
+                yield bytes(buffer)
 
     async def aclose(self) -> None:
         with map_httpcore_exceptions():
@@ -218,9 +615,21 @@ class AsyncHTTPTransport(AsyncBaseTransport):
         uds: str = None,
         local_address: str = None,
         retries: int = 0,
-        backend: str = "auto",
+        backend: typing.Union[str, "AsyncBackend"] = "auto",
+        read_size: int = None,
+        min_chunk_size: int = None,
+        failure_cache_ttl: float = None,
+        header_order: typing.Sequence[str] = None,
     ) -> None:
         ssl_context = create_ssl_context(verify=verify, cert=cert, trust_env=trust_env)
+        self._min_chunk_size = min_chunk_size
+        self._failure_cache = FailureCache(ttl=failure_cache_ttl)
+        self._header_order = build_header_order(header_order)
+
+        if read_size is not None:
+            backend = import_backends().SizedReadBackend(
+                backend, read_size=read_size
+            )
 
         if proxy is None:
             self._pool = httpcore.AsyncConnectionPool(
@@ -270,7 +679,15 @@ class AsyncHTTPTransport(AsyncBaseTransport):
     ) -> typing.Tuple[
         int, typing.List[typing.Tuple[bytes, bytes]], AsyncByteStream, dict
     ]:
-        with map_httpcore_exceptions():
+        headers = order_headers(headers, self._header_order)
+        affinity = extensions.get("connection_affinity")
+        connection_class = async_connection_class()
+        if affinity is not None and can_use_affinity(self._pool, connection_class):
+            return await self._handle_async_request_with_affinity(
+                affinity, connection_class, method, url, headers, stream, extensions
+            )
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
             (
                 status_code,
                 headers,
@@ -284,9 +701,114 @@ class AsyncHTTPTransport(AsyncBaseTransport):
                 extensions=extensions,
             )
 
-        stream = AsyncResponseStream(byte_stream)
+        stream = AsyncResponseStream(byte_stream, self._min_chunk_size)
+
+        return status_code, headers, stream, extensions
+
+    async def _handle_async_request_with_affinity(
+        self,
+        affinity: dict,
+        connection_class: typing.Any,
+        method: bytes,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        headers: typing.List[typing.Tuple[bytes, bytes]],
+        stream: AsyncByteStream,
+        extensions: dict,
+    ) -> typing.Tuple[
+        int, typing.List[typing.Tuple[bytes, bytes]], AsyncByteStream, dict
+    ]:
+        """
+        Send a request over the same connection as any previous requests sharing
+        the "connection_affinity" extension, as required by connection-oriented
+        authentication schemes such as NTLM.
+
+        The connection is kept out of the pool until a request is sent with
+        `affinity["release"] = True`, at which point it is added to the pool.
+        If the installed httpcore does not support this, requests are sent
+        through the pool as usual.
+        """
+        origin = url_to_origin(url)
+        timeout = extensions.get("timeout", {})
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
+            connection = affinity.get("connection")
+            if (
+                connection is None
+                or connection.origin != origin
+                or connection.should_close()
+            ):
+                connection = self._new_connection(origin, connection_class)
+                affinity["connection"] = connection
+
+            (
+                status_code,
+                headers,
+                byte_stream,
+                extensions,
+            ) = await connection.handle_async_request(
+                method=method,
+                url=url,
+                headers=headers,
+                stream=httpcore.AsyncIteratorByteStream(stream.__aiter__()),
+                extensions=extensions,
+            )
+
+            if affinity.get("release"):
+                del affinity["connection"]
+                await self._pool._add_to_pool(connection, timeout=timeout)
+
+        stream = AsyncResponseStream(byte_stream, self._min_chunk_size)
 
         return status_code, headers, stream, extensions
 
+    def _new_connection(
+        self, origin: typing.Tuple[bytes, bytes, int], connection_class: typing.Any
+    ) -> typing.Any:
+        pool = self._pool
+        return connection_class(
+            origin=origin,
+            http1=pool._http1,
+            http2=pool._http2,
+            keepalive_expiry=pool._keepalive_expiry,
+            uds=pool._uds,
+            ssl_context=pool._ssl_context,
+            local_address=pool._local_address,
+            retries=pool._retries,
+            backend=pool._backend,
+        )
+
+    async def apreconnect(
+        self,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        count: int = 1,
+        extensions: dict = None,
+    ) -> None:
+        connection_class = async_connection_class()
+        if not can_preconnect(self._pool, connection_class):
+            return
+
+        origin = url_to_origin(url)
+        timeout = {} if extensions is None else extensions.get("timeout", {})
+        pool = self._pool
+
+        with self._failure_cache.track(url), map_httpcore_exceptions():
+            for _ in range(count):
+                connection = self._new_connection(origin, connection_class)
+                await pool._add_to_pool(connection, timeout=timeout)
+                try:
+                    async with connection.request_lock:
+                        connection.socket = await connection._open_socket(timeout)
+                        connection._create_connection(connection.socket)
+                except BaseException:
+                    await pool._remove_from_pool(connection)
+                    raise
+
+    def clear_failure_cache(self, url: URLTypes = None) -> None:
+        """
+        Forget any cached connection failure for the origin of the given URL,
+        or for all origins if no URL is given.
+        """
+        self._failure_cache.clear(url)
+
     async def aclose(self) -> None:
         await self._pool.aclose()
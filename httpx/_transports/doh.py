@@ -0,0 +1,232 @@
+"""
+DNS-over-HTTPS name resolution, as described in RFC 8484.
+
+Example usage...
+
+# Resolve hostnames using Cloudflare's DoH service, bootstrapping the
+# connection to the resolver itself using a fixed IP address.
+resolver = httpx.DoHResolver(
+    "https://cloudflare-dns.com/dns-query", bootstrap=["1.1.1.1"]
+)
+transport = httpx.HTTPTransport(resolver=resolver)
+client = httpx.Client(transport=transport)
+"""
+import socket
+import struct
+import threading
+import typing
+
+from .._clock import monotonic
+from .._config import DEFAULT_TIMEOUT_CONFIG, Timeout
+from .._exceptions import HTTPError
+from .._models import URL, Request
+from .._types import TimeoutTypes, URLTypes, VerifyTypes
+from .base import BaseTransport
+from .default import HTTPTransport
+
+DNS_TYPE_A = 1
+DNS_TYPE_AAAA = 28
+DNS_CLASS_IN = 1
+DNS_MESSAGE_CONTENT_TYPE = b"application/dns-message"
+MAX_DNS_LABEL_LENGTH = 63
+MAX_DNS_NAME_LENGTH = 255
+
+
+def build_dns_query(host: str, record_type: int) -> bytes:
+    """
+    Build a DNS query message for the given hostname and record type.
+
+    RFC 8484 recommends using a message ID of zero, for cache friendliness.
+    Raises `ValueError` for hostnames that cannot be encoded, such as those
+    with empty labels, or labels longer than 63 bytes.
+    """
+    labels = host.encode("ascii").split(b".")
+    if labels[-1] == b"":
+        # A fully qualified name, such as "example.com.", ends with the root label.
+        labels.pop()
+    if not labels:
+        raise ValueError(f"Invalid hostname {host!r}")
+    for label in labels:
+        if not 0 < len(label) <= MAX_DNS_LABEL_LENGTH:
+            raise ValueError(f"Invalid DNS label {label!r} in hostname {host!r}")
+
+    # ID=0, flags=RD ("recursion desired"), QDCOUNT=1, ANCOUNT=NSCOUNT=ARCOUNT=0
+    header = struct.pack("!HHHHHH", 0, 0x0100, 1, 0, 0, 0)
+    qname = b"".join([bytes([len(label)]) + label for label in labels]) + b"\x00"
+    if len(qname) > MAX_DNS_NAME_LENGTH:
+        raise ValueError(f"Hostname {host!r} is too long for a DNS query")
+    return header + qname + struct.pack("!HH", record_type, DNS_CLASS_IN)
+
+
+def _is_ip_address(host: str) -> bool:
+    """
+    Return `True` if the host is an IPv4 or IPv6 address literal.
+    """
+    for family in (socket.AF_INET, socket.AF_INET6):
+        try:
+            socket.inet_pton(family, host)
+        except (OSError, ValueError):
+            continue
+        return True
+    return False
+
+
+def _skip_dns_name(message: bytes, offset: int) -> int:
+    """
+    Return the offset immediately following the name starting at `offset`.
+    """
+    while True:
+        length = message[offset]
+        if length & 0xC0 == 0xC0:
+            # A compression pointer terminates the name.
+            return offset + 2
+        if length == 0:
+            return offset + 1
+        offset += length + 1
+
+
+def parse_dns_response(message: bytes) -> typing.List[typing.Tuple[str, int]]:
+    """
+    Parse a DNS response message, returning a list of `(address, ttl)` pairs
+    for any A and AAAA records included in the answer section.
+    """
+    try:
+        _, flags, qdcount, ancount, _, _ = struct.unpack_from("!HHHHHH", message)
+        rcode = flags & 0x000F
+        if rcode != 0:
+            raise socket.gaierror(socket.EAI_NONAME, f"DNS error code {rcode}")
+
+        offset = 12
+        for _ in range(qdcount):
+            offset = _skip_dns_name(message, offset) + 4
+
+        answers = []
+        for _ in range(ancount):
+            offset = _skip_dns_name(message, offset)
+            rtype, rclass, ttl, rdlength = struct.unpack_from(
+                "!HHIH", message, offset
+            )
+            offset += 10
+            rdata = message[offset : offset + rdlength]
+            offset += rdlength
+            if rclass != DNS_CLASS_IN:
+                continue
+            if rtype == DNS_TYPE_A and rdlength == 4:
+                answers.append((socket.inet_ntop(socket.AF_INET, rdata), ttl))
+            elif rtype == DNS_TYPE_AAAA and rdlength == 16:
+                answers.append((socket.inet_ntop(socket.AF_INET6, rdata), ttl))
+    except (struct.error, IndexError):
+        raise socket.gaierror(socket.EAI_FAIL, "Malformed DNS response") from None
+
+    return answers
+
+
+class DoHResolver:
+    """
+    Resolves hostnames using DNS-over-HTTPS, for use with `HTTPTransport`.
+
+    * `url` - The URL of the DoH service.
+    * `bootstrap` - *(optional)* IP addresses to use when connecting to the DoH
+      service itself. If not provided the system resolver is used for this.
+    * `verify` - *(optional)* SSL verification for the DoH service.
+    * `timeout` - *(optional)* The timeout configuration for DoH queries.
+    * `transport` - *(optional)* A transport to use for sending DoH queries.
+
+    Results are cached for the TTL provided in the DNS response. Queries are
+    blocking, so the resolver is only supported by the sync `HTTPTransport`.
+    """
+
+    def __init__(
+        self,
+        url: URLTypes = "https://cloudflare-dns.com/dns-query",
+        *,
+        bootstrap: typing.Sequence[str] = None,
+        verify: VerifyTypes = True,
+        timeout: TimeoutTypes = DEFAULT_TIMEOUT_CONFIG,
+        transport: BaseTransport = None,
+    ) -> None:
+        self.url = URL(url)
+        self.timeout = Timeout(timeout)
+        if transport is None:
+            resolver = None if not bootstrap else _BootstrapResolver(bootstrap)
+            transport = HTTPTransport(verify=verify, resolver=resolver)
+        self._transport = transport
+        self._cache: typing.Dict[str, typing.Tuple[float, typing.List[str]]] = {}
+        self._lock = threading.Lock()
+
+    def resolve(self, host: str) -> typing.List[str]:
+        """
+        Return the IP addresses for a hostname, with IPv4 addresses listed first.
+        """
+        if _is_ip_address(host):
+            # IP address literals are connected to directly, without a query.
+            return [host]
+
+        with self._lock:
+            cached = self._cache.get(host)
+            if cached is not None and cached[0] > monotonic():
+                return list(cached[1])
+
+        answers = []
+        for record_type in (DNS_TYPE_A, DNS_TYPE_AAAA):
+            answers.extend(self._query(host, record_type))
+
+        if not answers:
+            raise socket.gaierror(socket.EAI_NONAME, f"No addresses for {host!r}")
+
+        addresses = [address for address, _ in answers]
+        expires = monotonic() + min(ttl for _, ttl in answers)
+        with self._lock:
+            self._cache[host] = (expires, addresses)
+        return list(addresses)
+
+    def _query(
+        self, host: str, record_type: int
+    ) -> typing.List[typing.Tuple[str, int]]:
+        try:
+            query = build_dns_query(host, record_type)
+        except ValueError as exc:
+            raise socket.gaierror(socket.EAI_NONAME, str(exc)) from None
+
+        request = Request(
+            "POST",
+            self.url,
+            headers={
+                b"Accept": DNS_MESSAGE_CONTENT_TYPE,
+                b"Content-Type": DNS_MESSAGE_CONTENT_TYPE,
+            },
+            content=query,
+        )
+        try:
+            status_code, _, stream, _ = self._transport.handle_request(
+                request.method.encode(),
+                request.url.raw,
+                headers=request.headers.raw,
+                stream=request.stream,
+                extensions={"timeout": self.timeout.as_dict()},
+            )
+            content = stream.read()
+        except HTTPError as exc:
+            message = f"DoH query failed: {exc}"
+            raise socket.gaierror(socket.EAI_AGAIN, message) from exc
+
+        if status_code != 200:
+            message = f"DoH query failed with status code {status_code}"
+            raise socket.gaierror(socket.EAI_AGAIN, message)
+        return parse_dns_response(content)
+
+    def close(self) -> None:
+        self._transport.close()
+
+
+class _BootstrapResolver:
+    """
+    Resolves any hostname to a fixed set of addresses. Used for connecting
+    to the DoH service itself.
+    """
+
+    def __init__(self, addresses: typing.Sequence[str]) -> None:
+        self._addresses = list(addresses)
+
+    def resolve(self, host: str) -> typing.List[str]:
+        return list(self._addresses)
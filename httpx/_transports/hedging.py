@@ -0,0 +1,121 @@
+import typing
+from concurrent.futures import FIRST_COMPLETED, Future, ThreadPoolExecutor, wait
+
+from .._content import ByteStream
+from .base import BaseTransport, SyncByteStream
+
+# Request methods that may safely be sent more than once.
+# See https://tools.ietf.org/html/rfc7231#section-4.2.2
+IDEMPOTENT_METHODS = (b"GET", b"HEAD", b"OPTIONS", b"PUT", b"DELETE", b"TRACE")
+
+_Response = typing.Tuple[
+    int, typing.List[typing.Tuple[bytes, bytes]], SyncByteStream, dict
+]
+
+
+def _close_response(future: "Future[_Response]") -> None:
+    if not future.cancelled() and future.exception() is None:
+        _, _, stream, _ = future.result()
+        stream.close()
+
+
+class HedgingTransport(BaseTransport):
+    """
+    A custom transport that reduces tail latency by hedging requests.
+
+    If no response has been received after `delay` seconds, a duplicate of the
+    request is sent, and whichever response arrives first is used. The slower
+    request is cancelled if it has not yet started. A request that is already
+    in flight cannot be interrupted from another thread, so its connection is
+    held until its response arrives, at which point the response is closed
+    without reading the body, and the connection is released.
+
+    Hedging runs requests in threads, so this transport is only available for
+    the sync `Client`.
+
+    ```
+    transport = httpx.HedgingTransport(httpx.HTTPTransport(), delay=0.1)
+    client = httpx.Client(transport=transport)
+    ```
+
+    Only idempotent requests are hedged. Other requests are sent once, as usual.
+
+    Arguments:
+
+    * `transport` - The transport used for sending requests.
+    * `delay` - The time in seconds to wait for a response before hedging.
+    * `hedge_transport` - *(optional)* The transport used for sending the
+      duplicate request, for example one that connects to an alternate address.
+      Defaults to `transport`.
+    """
+
+    def __init__(
+        self,
+        transport: BaseTransport,
+        delay: float,
+        hedge_transport: BaseTransport = None,
+    ) -> None:
+        self._transport = transport
+        self._delay = delay
+        self._hedge_transport = (
+            transport if hedge_transport is None else hedge_transport
+        )
+
+    def handle_request(
+        self,
+        method: bytes,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        headers: typing.List[typing.Tuple[bytes, bytes]],
+        stream: SyncByteStream,
+        extensions: dict,
+    ) -> _Response:
+        if method.upper() not in IDEMPOTENT_METHODS:
+            return self._transport.handle_request(
+                method, url, headers=headers, stream=stream, extensions=extensions
+            )
+
+        # The request body needs to be sent twice, so load it into memory.
+        content = b"".join(stream)
+
+        def send(transport: BaseTransport) -> _Response:
+            return transport.handle_request(
+                method,
+                url,
+                headers=headers,
+                stream=ByteStream(content),
+                extensions=extensions,
+            )
+
+        executor = ThreadPoolExecutor(max_workers=2)
+        try:
+            primary = executor.submit(send, self._transport)
+            done, _ = wait([primary], timeout=self._delay)
+            if done:
+                return primary.result()
+
+            hedge = executor.submit(send, self._hedge_transport)
+            pending = {primary, hedge}
+            winner: typing.Optional["Future[_Response]"] = None
+            while pending and winner is None:
+                done, pending = wait(pending, return_when=FIRST_COMPLETED)
+                successful = [future for future in done if future.exception() is None]
+                if successful:
+                    winner = successful[0]
+                    for future in successful[1:]:
+                        _close_response(future)
+
+            for future in pending:
+                if not future.cancel():
+                    future.add_done_callback(_close_response)
+
+            if winner is None:
+                # Both requests failed. Raise the error from the original request.
+                return primary.result()
+            return winner.result()
+        finally:
+            executor.shutdown(wait=False)
+
+    def close(self) -> None:
+        self._transport.close()
+        if self._hedge_transport is not self._transport:
+            self._hedge_transport.close()
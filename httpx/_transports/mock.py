@@ -1,6 +1,7 @@
 import asyncio
 import typing
 
+from .._diff import diff
 from .._models import Request
 from .base import AsyncBaseTransport, AsyncByteStream, BaseTransport, SyncByteStream
 
@@ -8,6 +9,19 @@ from .base import AsyncBaseTransport, AsyncByteStream, BaseTransport, SyncByteSt
 class MockTransport(AsyncBaseTransport, BaseTransport):
     def __init__(self, handler: typing.Callable) -> None:
         self.handler = handler
+        self.requests: typing.List[Request] = []
+
+    def assert_requested(self, expected: Request) -> None:
+        """
+        Assert that a request equivalent to `expected` has been sent, raising an
+        `AssertionError` with a diff against the most recent request otherwise.
+        """
+        if not self.requests:
+            raise AssertionError("No requests have been sent.")
+        differences = [diff(expected, request) for request in self.requests]
+        if "" not in differences:
+            message = "No matching request was sent. Most recent request differs:"
+            raise AssertionError(f"{message}\n{differences[-1]}")
 
     def handle_request(
         self,
@@ -26,6 +40,7 @@ class MockTransport(AsyncBaseTransport, BaseTransport):
             stream=stream,
         )
         request.read()
+        self.requests.append(request)
         response = self.handler(request)
         return (
             response.status_code,
@@ -51,6 +66,7 @@ class MockTransport(AsyncBaseTransport, BaseTransport):
             stream=stream,
         )
         await request.aread()
+        self.requests.append(request)
 
         response = self.handler(request)
 
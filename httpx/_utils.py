@@ -1,21 +1,36 @@
 import codecs
+import datetime
+import decimal
+import email.utils
+import enum
+import io
 import logging
 import mimetypes
 import netrc
 import os
 import re
+import stat
 import sys
-import time
 import typing
+import uuid
 from pathlib import Path
+from urllib.parse import quote as urllib_quote
+from urllib.parse import unquote_to_bytes
 from urllib.request import getproxies
 
 import sniffio
 
+from ._clock import monotonic, now
+from ._exceptions import LocalProtocolError
 from ._types import PrimitiveData
 
+try:
+    import mmap
+except ImportError:  # pragma: nocover
+    mmap = None  # type: ignore
+
 if typing.TYPE_CHECKING:  # pragma: no cover
-    from ._models import URL
+    from ._models import URL, Headers
 
 
 _HTML5_FORM_ENCODING_REPLACEMENTS = {'"': "%22", "\\": "\\\\"}
@@ -25,6 +40,13 @@ _HTML5_FORM_ENCODING_REPLACEMENTS.update(
 _HTML5_FORM_ENCODING_RE = re.compile(
     r"|".join([re.escape(c) for c in _HTML5_FORM_ENCODING_REPLACEMENTS.keys()])
 )
+_PERCENT_ENCODED_RE = re.compile(r"%[0-9A-Fa-f]{2}")
+_ASCII_NON_PRINTABLE_RE = re.compile(r"[\x00-\x1f\x7f]")
+# Header names must be a "token", and header values must not contain CR, LF, or
+# NUL characters, which would allow request splitting.
+# See https://www.rfc-editor.org/rfc/rfc9110#section-5.1
+_HEADER_NAME_RE = re.compile(rb"[!#$%&'*+\-.^_`|~0-9A-Za-z]+")
+_HEADER_VALUE_INVALID_RE = re.compile(rb"[\r\n\x00]")
 
 
 def normalize_header_key(
@@ -40,9 +62,20 @@ def normalize_header_key(
     else:
         bytes_value = value.encode(encoding or "ascii")
 
+    if _HEADER_NAME_RE.fullmatch(bytes_value) is None:
+        raise LocalProtocolError(f"Invalid header name {bytes_value!r}")
+
     return bytes_value.lower() if lower else bytes_value
 
 
+def canonical_header_name(name: bytes) -> bytes:
+    """
+    Return the canonical casing of a header name, with the first letter of
+    each hyphenated word in uppercase, as in b"Content-Type" or b"X-Request-Id".
+    """
+    return b"-".join(part[:1].upper() + part[1:].lower() for part in name.split(b"-"))
+
+
 def normalize_header_value(
     value: typing.Union[str, bytes], encoding: str = None
 ) -> bytes:
@@ -50,8 +83,17 @@ def normalize_header_value(
     Coerce str/bytes into a strictly byte-wise HTTP header value.
     """
     if isinstance(value, bytes):
-        return value
-    return value.encode(encoding or "ascii")
+        bytes_value = value
+    else:
+        bytes_value = value.encode(encoding or "ascii")
+
+    if _HEADER_VALUE_INVALID_RE.search(bytes_value) is not None:
+        # The value itself is not included, since it may be sensitive.
+        raise LocalProtocolError(
+            "Invalid header value. Header values may not contain CR, LF, or NUL."
+        )
+
+    return bytes_value
 
 
 def primitive_value_to_str(value: "PrimitiveData") -> str:
@@ -59,6 +101,8 @@ def primitive_value_to_str(value: "PrimitiveData") -> str:
     Coerce a primitive data type into a string value.
 
     Note that we prefer JSON-style 'true'/'false' for boolean values here.
+    Dates and times use ISO 8601, floats and decimals use fixed-point notation,
+    and enums use their value. Other types use `str()`.
     """
     if value is True:
         return "true"
@@ -66,9 +110,55 @@ def primitive_value_to_str(value: "PrimitiveData") -> str:
         return "false"
     elif value is None:
         return ""
+    elif isinstance(value, enum.Enum):
+        return primitive_value_to_str(value.value)
+    elif isinstance(value, (datetime.date, datetime.time)):
+        return value.isoformat()
+    elif isinstance(value, decimal.Decimal) and value.is_finite():
+        return format(value, "f")
+    elif isinstance(value, float):
+        # Use the shortest representation that round-trips, as `repr()` does,
+        # but without switching to exponent notation for large or small values.
+        # Whole numbers keep a ".0" either side of the switch, as in "1e+16".
+        text = repr(value)
+        if "e" in text:
+            text = format(decimal.Decimal(text), "f")
+            if "." not in text:
+                text += ".0"
+        return text
+    elif isinstance(value, uuid.UUID):
+        return str(value)
     return str(value)
 
 
+def requote(value: str, safe: str = "/") -> str:
+    """
+    Percent-encode a string, leaving any existing percent-encoded sequences intact.
+
+    This means that partially escaped input such as "a=%20b c" is escaped
+    to "a=%20b%20c", rather than being double-escaped to "a=%2520b%20c".
+    Any "%" that does not begin a valid sequence, such as in "100%" or "%zz",
+    is escaped to "%25".
+    """
+    parts = []
+    position = 0
+    for match in _PERCENT_ENCODED_RE.finditer(value):
+        parts.append(urllib_quote(value[position : match.start()], safe=safe))
+        parts.append(match.group(0))
+        position = match.end()
+    parts.append(urllib_quote(value[position:], safe=safe))
+    return "".join(parts)
+
+
+def find_ascii_non_printable(value: str) -> typing.Optional[int]:
+    """
+    Return the index of the first non-printable ASCII character in a string,
+    such as a control character, tab, or newline. Returns `None` if there are none.
+    """
+    match = _ASCII_NON_PRINTABLE_RE.search(value)
+    return None if match is None else match.start()
+
+
 def is_known_encoding(encoding: str) -> bool:
     """
     Return `True` if `encoding` is a known codec.
@@ -172,6 +262,74 @@ def get_ca_bundle_from_env() -> typing.Optional[str]:
     return None
 
 
+_CONTENT_RANGE_RE = re.compile(r"^bytes\s+(\d+)-(\d+)/(\d+|\*)$", re.IGNORECASE)
+
+
+# The formats accepted by `parse_iso_datetime()`, once any UTC offset has been
+# normalized to the "+HHMM" form that `strptime()` supports on all versions.
+ISO_DATETIME_FORMATS = (
+    "%Y-%m-%dT%H:%M:%S.%f%z",
+    "%Y-%m-%dT%H:%M:%S%z",
+    "%Y-%m-%dT%H:%M%z",
+    "%Y-%m-%dT%H:%M:%S.%f",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d",
+)
+
+
+def parse_iso_datetime(value: str) -> datetime.datetime:
+    """
+    Parse an ISO 8601 date or datetime, such as "2021-05-01T12:00:00Z",
+    raising `ValueError` if it is invalid.
+    """
+    normalized = value.strip().replace(" ", "T", 1)
+    if normalized.endswith("Z"):
+        normalized = normalized[:-1] + "+0000"
+    normalized = re.sub(r"([+-]\d\d):(\d\d)$", r"\1\2", normalized)
+    for format in ISO_DATETIME_FORMATS:
+        try:
+            return datetime.datetime.strptime(normalized, format)
+        except ValueError:
+            pass
+    raise ValueError(f"Invalid ISO 8601 datetime: {value!r}")
+
+
+def parse_content_range(value: str) -> typing.Tuple[int, int, typing.Optional[int]]:
+    """
+    Parse a `Content-Range` header value, such as "bytes 0-499/1234", returning
+    `(first_byte, last_byte, complete_length)`. The complete length is `None`
+    if it is unknown, as in "bytes 0-499/*".
+    """
+    match = _CONTENT_RANGE_RE.match(value.strip())
+    if match is None:
+        raise ValueError(f"Invalid Content-Range {value!r}")
+    first, last, length = match.groups()
+    if int(last) < int(first):
+        raise ValueError(f"Invalid Content-Range {value!r}")
+    return int(first), int(last), None if length == "*" else int(length)
+
+
+def parse_retry_after(value: str) -> typing.Optional[float]:
+    """
+    Parse a 'Retry-After' header, given either as a number of seconds or as an
+    HTTP date, into the number of seconds to wait. Dates in the past give `0.0`.
+    Returns `None` if the value cannot be parsed.
+
+    See https://www.rfc-editor.org/rfc/rfc9110#section-10.2.3
+    """
+    value = value.strip()
+    if re.fullmatch("[0-9]+", value):
+        return float(value)
+    try:
+        date = email.utils.parsedate_to_datetime(value)
+    except (TypeError, ValueError, IndexError):
+        return None
+    if date.tzinfo is None:
+        date = date.replace(tzinfo=datetime.timezone.utc)
+    return max(0.0, date.timestamp() - now())
+
+
 def parse_header_links(value: str) -> typing.List[typing.Dict[str, str]]:
     """
     Returns a list of parsed link headers, for more info see:
@@ -209,14 +367,287 @@ def parse_header_links(value: str) -> typing.List[typing.Dict[str, str]]:
     return links
 
 
-SENSITIVE_HEADERS = {"authorization", "proxy-authorization"}
+# Headers with a single value which may itself contain commas, such as an
+# HTTP-date, and so are never split into a list of values.
+UNSPLITTABLE_HEADERS = {
+    "date",
+    "expires",
+    "if-modified-since",
+    "if-unmodified-since",
+    "last-modified",
+    "retry-after",
+    "set-cookie",
+}
+
+
+def split_header_value(value: str, separator: str = ",") -> typing.List[str]:
+    """
+    Split a comma separated header value into a list of values, ignoring any
+    commas within quoted strings, and any empty values. A different separator,
+    such as ";", may be given.
+
+    For example, 'a, "b, c", d' returns ['a', '"b, c"', 'd'].
+    """
+    items = []
+    start = 0
+    in_quotes = escaped = False
+    for index, char in enumerate(value):
+        if escaped:
+            escaped = False
+        elif char == "\\" and in_quotes:
+            escaped = True
+        elif char == '"':
+            in_quotes = not in_quotes
+        elif char == separator and not in_quotes:
+            items.append(value[start:index])
+            start = index + 1
+    items.append(value[start:])
+    return [item.strip() for item in items if item.strip()]
+
+
+_CONTENT_LENGTH_RE = re.compile(r"[0-9]+")
+
+
+def parse_content_length(headers: "Headers") -> typing.Optional[int]:
+    """
+    Return the 'Content-Length' of a message, or None if it has no such header.
+
+    Raises a ValueError if the header is not a non-negative integer, if it has
+    several conflicting values, or if 'Transfer-Encoding' is also present, as
+    any of these make the message framing ambiguous.
+
+    See https://www.rfc-editor.org/rfc/rfc9112#section-6.3
+    """
+    values = {
+        item.strip()
+        for value in headers.get_list("content-length")
+        for item in value.split(",")
+    }
+    if not values:
+        return None
+    for value in values:
+        if _CONTENT_LENGTH_RE.fullmatch(value) is None:
+            raise ValueError(f"Invalid Content-Length header: {value!r}")
+    lengths = {int(value) for value in values}
+    if len(lengths) > 1:
+        raise ValueError("Conflicting Content-Length headers")
+    if "transfer-encoding" in headers:
+        raise ValueError("Content-Length and Transfer-Encoding headers both present")
+    return lengths.pop()
+
+
+_SET_COOKIE_START_RE = re.compile(r"[!#$%&'*+\-.^_`|~0-9A-Za-z]+[ \t]*=")
+
+
+def split_set_cookie_header(value: str) -> typing.List[str]:
+    """
+    Split a comma joined 'Set-Cookie' header into separate cookies. Commas
+    only separate cookies when followed by a new 'name=value' pair, so dates
+    such as 'Expires=Wed, 21 Oct 2015 07:28:00 GMT' are kept intact.
+
+    For example, 'a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT, b=2' returns
+    ['a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT', 'b=2'].
+    """
+    cookies: typing.List[str] = []
+    for item in split_header_value(value):
+        if cookies and _SET_COOKIE_START_RE.match(item) is None:
+            cookies[-1] = f"{cookies[-1]}, {item}"
+        else:
+            cookies.append(item)
+    return cookies
+
+
+_QUOTED_PAIR_RE = re.compile(r"\\(.)")
+
+
+class ContentType(typing.NamedTuple):
+    """
+    A parsed 'Content-Type' header, such as 'text/html; charset="utf-8"'.
+    """
+
+    type: str
+    subtype: str
+    params: typing.Dict[str, str]
+
+    @property
+    def media_type(self) -> str:
+        return f"{self.type}/{self.subtype}" if self.subtype else self.type
+
+
+def parse_header_params(value: str) -> typing.Tuple[str, typing.Dict[str, str]]:
+    """
+    Parse a header of the form 'value; name=param; ...', returning the
+    lowercased value and a dict of parameters. Parameter names are lowercased,
+    and parameter values may be quoted strings, which are unquoted.
+    """
+    main_value, *parameters = split_header_value(value, separator=";") or [""]
+    params = {}
+    for parameter in parameters:
+        name, _, param_value = parameter.partition("=")
+        param_value = param_value.strip()
+        if len(param_value) >= 2 and param_value[0] == param_value[-1] == '"':
+            param_value = _QUOTED_PAIR_RE.sub(r"\1", param_value[1:-1])
+        params[name.strip().lower()] = param_value
+    return main_value.strip().lower(), params
+
+
+def parse_content_type(value: str) -> ContentType:
+    """
+    Parse a 'Content-Type' header. The type, subtype, and parameter names are
+    case-insensitive, and so are lowercased.
+
+    See https://www.rfc-editor.org/rfc/rfc9110#section-8.3
+    """
+    media_type, params = parse_header_params(value)
+    type_, _, subtype = media_type.partition("/")
+    return ContentType(type_.strip(), subtype.strip(), params)
+
+
+_QVALUE_RE = re.compile(r"0(\.[0-9]{0,3})?|1(\.0{0,3})?")
+
+
+def parse_quality_values(value: str) -> typing.List[typing.Tuple[str, float]]:
+    """
+    Parse an 'Accept' style header, such as 'Accept-Encoding' or
+    'Accept-Language', into a list of (value, q) pairs, sorted by preference.
+    Values without a 'q' parameter have a quality of 1. Values with equal
+    quality keep the order they were given in. Any other parameters remain
+    part of the value.
+
+    For example, 'gzip;q=0.5, br, *;q=0' returns
+    [('br', 1.0), ('gzip', 0.5), ('*', 0.0)].
+
+    Raises a ValueError if a 'q' parameter is not a valid qvalue.
+
+    See https://www.rfc-editor.org/rfc/rfc9110#section-12.4.2
+    """
+    items = []
+    for item in split_header_value(value):
+        main_value, *parameters = split_header_value(item, separator=";") or [""]
+        quality = 1.0
+        other_parameters = []
+        for parameter in parameters:
+            name, _, param_value = parameter.partition("=")
+            if name.strip().lower() == "q":
+                param_value = param_value.strip()
+                if _QVALUE_RE.fullmatch(param_value) is None:
+                    raise ValueError(f"Invalid quality value: {param_value!r}")
+                quality = float(param_value)
+            else:
+                other_parameters.append(parameter)
+        items.append((";".join([main_value, *other_parameters]), quality))
+    return sorted(items, key=lambda item: -item[1])
+
+
+def parse_content_disposition(
+    value: str,
+) -> typing.Tuple[str, typing.Dict[str, str]]:
+    """
+    Parse a 'Content-Disposition' header, returning the disposition type, such
+    as "attachment", and a dict of parameters.
+
+    Extended parameters, such as "filename*=UTF-8''na%C3%AFve.txt", are decoded
+    and take precedence over the plain parameter of the same name, which is
+    kept as a fallback for clients that do not support them.
+
+    See https://www.rfc-editor.org/rfc/rfc6266 and
+    https://www.rfc-editor.org/rfc/rfc8187
+    """
+    disposition, params = parse_header_params(value)
+    for name in [name for name in params if name.endswith("*")]:
+        encoded = params.pop(name)
+        charset, _, rest = encoded.partition("'")
+        _, _, pct_encoded = rest.partition("'")
+        if charset.lower() not in ("utf-8", "iso-8859-1"):
+            continue
+        try:
+            params[name[:-1]] = unquote_to_bytes(pct_encoded).decode(charset)
+        except UnicodeDecodeError:
+            continue
+    return disposition, params
+
+
+_TOKEN68_RE = re.compile(r"[A-Za-z0-9\-._~+/]+=*")
+_AUTH_SCHEME_RE = re.compile(r"([!#$%&'*+\-.^_`|~0-9A-Za-z]+)(?:[ \t]+(.*))?")
+
+
+class AuthChallenge(typing.NamedTuple):
+    """
+    An authentication challenge, from a 'WWW-Authenticate' or
+    'Proxy-Authenticate' header. Challenges have either a `token`, such as
+    'Negotiate <base64 token>', or `params`, such as 'Digest realm="...", ...'.
+    """
+
+    scheme: str
+    token: typing.Optional[str]
+    params: typing.Dict[str, str]
+
+
+def parse_auth_challenges(value: str) -> typing.List[AuthChallenge]:
+    """
+    Parse a 'WWW-Authenticate' or 'Proxy-Authenticate' header, which may include
+    several comma separated challenges, such as 'Basic realm="a", Digest ...'.
+    Schemes and parameter names are lowercased, and quoted parameter values are
+    unquoted.
+
+    See https://www.rfc-editor.org/rfc/rfc9110#section-11.6.1
+    """
+    challenges: typing.List[AuthChallenge] = []
+    for item in split_header_value(value):
+        name, sep, param_value = item.partition("=")
+        match = _AUTH_SCHEME_RE.fullmatch(item)
+        if match is not None and not (match.group(2) or "").startswith("="):
+            # The start of a new challenge, followed by either a token68,
+            # the first of its parameters, or nothing at all.
+            scheme, rest = match.group(1).lower(), (match.group(2) or "").strip()
+            challenges.append(AuthChallenge(scheme, None, {}))
+            if not rest:
+                continue
+            if _TOKEN68_RE.fullmatch(rest) or "=" not in rest:
+                # Malformed tokens are kept, to be rejected by the scheme.
+                challenges[-1] = AuthChallenge(scheme, rest, {})
+                continue
+            name, sep, param_value = rest.partition("=")
+        if not challenges or not sep:
+            # Parameters must follow a scheme.
+            continue
+        param_value = param_value.strip()
+        if len(param_value) >= 2 and param_value[0] == param_value[-1] == '"':
+            param_value = _QUOTED_PAIR_RE.sub(r"\1", param_value[1:-1])
+        challenges[-1].params[name.strip().lower()] = param_value
+    return challenges
+
+
+def format_content_disposition(disposition: str, filename: str = None) -> str:
+    """
+    Build a 'Content-Disposition' header, such as for a download.
+
+    Filenames that are not ASCII are given as an extended "filename*"
+    parameter, encoded as UTF-8, along with an ASCII "filename" fallback in
+    which any other characters are replaced with "_".
+    """
+    if filename is None:
+        return disposition
+
+    fallback = "".join(char if " " <= char < "\x7f" else "_" for char in filename)
+    quoted = fallback.replace("\\", "\\\\").replace('"', '\\"')
+    if fallback == filename:
+        return f'{disposition}; filename="{quoted}"'
+    encoded = urllib_quote(filename, safe="!#$&+-.^_`|~")
+    return f"{disposition}; filename=\"{quoted}\"; filename*=UTF-8''{encoded}"
+
+
+SENSITIVE_HEADERS = frozenset(
+    {"authorization", "cookie", "proxy-authorization", "set-cookie"}
+)
 
 
 def obfuscate_sensitive_headers(
-    items: typing.Iterable[typing.Tuple[typing.AnyStr, typing.AnyStr]]
+    items: typing.Iterable[typing.Tuple[typing.AnyStr, typing.AnyStr]],
+    sensitive: typing.AbstractSet[str] = SENSITIVE_HEADERS,
 ) -> typing.Iterator[typing.Tuple[typing.AnyStr, typing.AnyStr]]:
     for k, v in items:
-        if to_str(k.lower()) in SENSITIVE_HEADERS:
+        if to_str(k.lower()) in sensitive:
             v = to_bytes_or_str("[secure]", match_type_of=v)
         yield k, v
 
@@ -265,21 +696,145 @@ def get_logger(name: str) -> Logger:
     return typing.cast(Logger, logger)
 
 
+# Default ports, as used for port normalization.
+# See https://url.spec.whatwg.org/#special-scheme
+DEFAULT_PORTS = {"ftp": 21, "http": 80, "https": 443, "ws": 80, "wss": 443}
+
+
 def port_or_default(url: "URL") -> typing.Optional[int]:
     if url.port is not None:
         return url.port
-    return {"http": 80, "https": 443}.get(url.scheme)
+    return DEFAULT_PORTS.get(url.scheme)
 
 
 def same_origin(url: "URL", other: "URL") -> bool:
     """
     Return 'True' if the given URLs share the same origin.
     """
-    return (
-        url.scheme == other.scheme
-        and url.host == other.host
-        and port_or_default(url) == port_or_default(other)
-    )
+    return url.origin == other.origin
+
+
+# See https://www.w3.org/TR/referrer-policy/#referrer-policies
+REFERRER_POLICIES = (
+    "no-referrer",
+    "no-referrer-when-downgrade",
+    "same-origin",
+    "origin",
+    "strict-origin",
+    "origin-when-cross-origin",
+    "strict-origin-when-cross-origin",
+    "unsafe-url",
+)
+
+
+def parse_referrer_policy(value: str) -> typing.Optional[str]:
+    """
+    Return the policy given by a 'Referrer-Policy' header value, or `None` if
+    no known policy is included. Where several policies are listed, the last
+    known policy takes precedence, so that new policies may be introduced with
+    a fallback.
+
+    See https://www.w3.org/TR/referrer-policy/#parse-referrer-policy-from-header
+    """
+    policy = None
+    for token in value.split(","):
+        token = token.strip().lower()
+        if token in REFERRER_POLICIES:
+            policy = token
+    return policy
+
+
+def referrer_for_url(referrer: "URL", url: "URL", policy: str) -> typing.Optional[str]:
+    """
+    Return the 'Referer' header value that should be used when sending a
+    request to `url` from the `referrer` URL, or `None` if the header should
+    be omitted under the given referrer policy.
+
+    See https://www.w3.org/TR/referrer-policy/#determine-requests-referrer
+    """
+    if policy == "no-referrer" or referrer.scheme not in ("http", "https"):
+        return None
+
+    # Credentials and fragments are never included in the 'Referer' header.
+    full = str(referrer.copy_with(userinfo=b"", fragment=None))
+    origin = str(referrer.copy_with(userinfo=b"", raw_path=b"/", fragment=None))
+    downgrade = referrer.scheme == "https" and url.scheme not in ("https", "wss")
+    cross_origin = not same_origin(referrer, url)
+
+    if policy == "unsafe-url":
+        return full
+    elif policy == "origin":
+        return origin
+    elif policy == "strict-origin":
+        return None if downgrade else origin
+    elif policy == "no-referrer-when-downgrade":
+        return None if downgrade else full
+    elif policy == "same-origin":
+        return None if cross_origin else full
+    elif policy == "origin-when-cross-origin":
+        return origin if cross_origin else full
+    elif policy == "strict-origin-when-cross-origin":
+        if not cross_origin:
+            return full
+        return None if downgrade else origin
+    raise ValueError(f"Unknown referrer policy {policy!r}")
+
+
+_IPV4_RE = re.compile(
+    r"^(?:(?:25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9]?[0-9])\.){3}"
+    r"(?:25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9]?[0-9])$"
+)
+_IPV6_GROUP_RE = re.compile(r"^[0-9A-Fa-f]{1,4}$")
+
+
+def is_ipv4_address(host: str) -> bool:
+    """
+    Return `True` if the host is an IPv4 address in dotted-decimal form.
+    """
+    return _IPV4_RE.match(host) is not None
+
+
+def is_ipv6_address(host: str) -> bool:
+    """
+    Return `True` if the host is an IPv6 address, without enclosing brackets.
+    Any zone identifier, such as "fe80::1%eth0", is permitted.
+
+    This avoids using the `ipaddress` module, which is comparatively slow.
+    """
+    address = host.partition("%")[0]
+    head, compressed, tail = address.partition("::")
+    if "::" in tail:
+        return False
+
+    groups = (head.split(":") if head else []) + (tail.split(":") if tail else [])
+    count = len(groups)
+    if groups and "." in groups[-1] and not address.endswith(":"):
+        # The final 32 bits may be written as an IPv4 address.
+        if not is_ipv4_address(groups.pop()):
+            return False
+        count += 1
+
+    if not all(_IPV6_GROUP_RE.match(group) for group in groups):
+        return False
+    return count < 8 if compressed else count == 8
+
+
+def classify_host(host: str) -> str:
+    """
+    Return "ipv4", "ipv6", or "domain", classifying a URL host.
+    IPv6 addresses may optionally be enclosed in brackets.
+
+    classify_host("127.0.0.1") == "ipv4"
+    classify_host("[::1]") == "ipv6"
+    classify_host("example.com") == "domain"
+    """
+    if host.startswith("[") and host.endswith("]"):
+        host = host[1:-1]
+    if is_ipv4_address(host):
+        return "ipv4"
+    elif is_ipv6_address(host):
+        return "ipv6"
+    return "domain"
 
 
 def get_environment_proxies() -> typing.Dict[str, typing.Optional[str]]:
@@ -342,6 +897,80 @@ def guess_content_type(filename: typing.Optional[str]) -> typing.Optional[str]:
     return None
 
 
+# The number of bytes of response content used to detect its type.
+CONTENT_SNIFF_SIZE = 512
+
+_ZIP_CONTENT_TYPES = (
+    "application/zip",
+    "application/x-zip-compressed",
+    "application/java-archive",
+)
+_EXECUTABLE_CONTENT_TYPES = (
+    "application/vnd.microsoft.portable-executable",
+    "application/x-msdownload",
+    "application/x-msdos-program",
+    "application/x-dosexec",
+    "application/x-executable",
+    "application/x-sharedlib",
+)
+_HTML_CONTENT_TYPES = ("text/html", "application/xhtml+xml")
+_HTML_TAGS = (b"<!doctype html", b"<html", b"<head", b"<body", b"<script", b"<iframe")
+
+
+def _is_portable_executable(data: bytes) -> bool:
+    """
+    Return `True` if the data starts with a DOS header whose `e_lfanew` field,
+    at offset 0x3C, points to a "PE\\0\\0" signature. The two byte "MZ" magic
+    number on its own would also match ordinary text.
+    """
+    if len(data) < 64:
+        return False
+    offset = int.from_bytes(data[60:64], "little")
+    return data[offset : offset + 4] == b"PE\x00\x00"
+
+
+def sniff_content_type(data: bytes) -> typing.Optional[str]:
+    """
+    Detect archive, executable, or HTML content from its first few bytes,
+    returning a media type, or `None` if the content is not recognised.
+
+    See https://mimesniff.spec.whatwg.org/
+    """
+    if data.startswith((b"PK\x03\x04", b"PK\x05\x06", b"PK\x07\x08")):
+        return "application/zip"
+    elif data.startswith(b"MZ") and _is_portable_executable(data):
+        return "application/x-msdownload"
+    elif data.startswith(b"\x7fELF"):
+        return "application/x-executable"
+
+    data = data.lstrip(b"\t\n\x0c\r ").lower()
+    for tag in _HTML_TAGS:
+        if data.startswith(tag) and data[len(tag) : len(tag) + 1] in (b" ", b">"):
+            return "text/html"
+    return None
+
+
+def is_compatible_content_type(declared: str, sniffed: str) -> bool:
+    """
+    Return `True` if a declared 'Content-Type' header is consistent with the
+    media type detected by `sniff_content_type()`. Missing or generic binary
+    content types are consistent with any content.
+    """
+    media_type = parse_content_type(declared).media_type
+    if media_type in ("", "application/octet-stream"):
+        return True
+    elif sniffed == "application/zip":
+        # Many document and package formats are zip archives.
+        return (
+            media_type in _ZIP_CONTENT_TYPES
+            or media_type.endswith("+zip")
+            or media_type.startswith("application/vnd.")
+        )
+    elif sniffed == "text/html":
+        return media_type in _HTML_CONTENT_TYPES
+    return media_type in _EXECUTABLE_CONTENT_TYPES
+
+
 def peek_filelike_length(stream: typing.Any) -> typing.Optional[int]:
     """
     Given a file-like stream object, return its length in number of bytes
@@ -367,6 +996,64 @@ def peek_filelike_length(stream: typing.Any) -> typing.Optional[int]:
     return length
 
 
+# The size of each window sent from a memory mapped file.
+MMAP_WINDOW_SIZE = 1024 * 1024
+
+
+def mappable_fileno(stream: typing.Any) -> typing.Optional[int]:
+    """
+    Return the file descriptor of a binary file that may be memory mapped, or
+    `None` if the stream is not a regular file, or if memory mapping is not
+    supported.
+    """
+    if mmap is None or not isinstance(stream, (io.BufferedReader, io.FileIO)):
+        return None
+    try:
+        fd = stream.fileno()
+        return fd if stat.S_ISREG(os.fstat(fd).st_mode) else None
+    except (OSError, ValueError):
+        return None
+
+
+def iter_file(
+    stream: typing.Iterable[typing.Any], use_mmap: bool = False
+) -> typing.Iterator[typing.Any]:
+    """
+    Iterate over the content of a file, or any other iterable, from its current
+    position.
+
+    With `use_mmap=True`, regular binary files are memory mapped where
+    supported, so that even very large files are sent directly from `memoryview`
+    windows onto the file, without copying them or reading them through the file
+    object. Each window is mapped separately, and unmapped once it is no longer
+    referenced. Anything else is iterated over as-is.
+
+    A file that is truncated while it is mapped crashes the process with SIGBUS
+    when the missing pages are read, which is why this is not the default.
+    """
+    fd = mappable_fileno(stream) if use_mmap else None
+    if fd is None:
+        yield from stream
+        return
+
+    size = os.fstat(fd).st_size
+    offset = stream.tell()  # type: ignore
+    while offset < size:
+        end = min(offset + MMAP_WINDOW_SIZE, size)
+        # Mappings must start at a multiple of the allocation granularity.
+        start = offset - offset % mmap.ALLOCATIONGRANULARITY
+        try:
+            window = mmap.mmap(fd, end - start, offset=start, access=mmap.ACCESS_READ)
+        except OSError:
+            # Fall back to reading the rest of the file.
+            yield from stream
+            return
+        yield memoryview(window)[offset - start :]
+        offset = end
+        # Keep the file position in step, as if the file had been read.
+        stream.seek(offset)  # type: ignore
+
+
 class Timer:
     async def _get_time(self) -> float:
         library = sniffio.current_async_library()
@@ -384,14 +1071,13 @@ class Timer:
         return asyncio.get_event_loop().time()
 
     def sync_start(self) -> None:
-        self.started = time.perf_counter()
+        self.started = monotonic()
 
     async def async_start(self) -> None:
         self.started = await self._get_time()
 
     def sync_elapsed(self) -> float:
-        now = time.perf_counter()
-        return now - self.started
+        return monotonic() - self.started
 
     async def async_elapsed(self) -> float:
         now = await self._get_time()
@@ -450,7 +1136,8 @@ class URLPattern:
                 f'Instead of "{pattern}", use "{pattern}://"'
             )
 
-        url = URL(pattern)
+        # Patterns such as "http://" match any host, but are not valid URLs.
+        url = URL(pattern + "*" if pattern.endswith("://") else pattern)
         self.pattern = pattern
         self.scheme = "" if url.scheme == "all" else url.scheme
         self.host = "" if url.host == "*" else url.host
@@ -30,10 +30,22 @@ async def test_get(server):
 @pytest.mark.usefixtures("async_environment")
 async def test_get_invalid_url(server, url):
     async with httpx.AsyncClient() as client:
-        with pytest.raises((httpx.UnsupportedProtocol, httpx.LocalProtocolError)):
+        with pytest.raises(
+            (httpx.UnsupportedProtocol, httpx.LocalProtocolError, httpx.InvalidURL)
+        ):
             await client.get(url)
 
 
+@pytest.mark.usefixtures("async_environment")
+async def test_read_size(server):
+    transport = httpx.AsyncHTTPTransport(read_size=4, min_chunk_size=8)
+    async with httpx.AsyncClient(transport=transport) as client:
+        async with client.stream("GET", server.url) as response:
+            chunks = [chunk async for chunk in response.aiter_raw()]
+    assert all(len(chunk) >= 8 for chunk in chunks[:-1])
+    assert b"".join(chunks) == b"Hello, world!"
+
+
 @pytest.mark.usefixtures("async_environment")
 async def test_build_request(server):
     url = server.url.copy_with(path="/echo_headers")
@@ -333,3 +345,29 @@ async def test_server_extensions(server):
         response = await client.get(url)
     assert response.status_code == 200
     assert response.extensions["http_version"] == b"HTTP/1.1"
+
+
+@pytest.mark.usefixtures("async_environment")
+async def test_preconnect(server):
+    async with httpx.AsyncClient() as client:
+        await client.preconnect(server.url, count=2)
+        response = await client.get(server.url)
+    assert response.status_code == 200
+
+
+@pytest.mark.usefixtures("async_environment")
+async def test_capture_approximate_request():
+    async def hello_world(request):
+        return httpx.Response(200, text="Hello, world!")
+
+    async def stream():
+        yield b"01234"
+        yield b"56789"
+
+    transport = httpx.MockTransport(hello_world)
+    async with httpx.AsyncClient(transport=transport, capture_approximate_request=7) as client:
+        response = await client.post("https://example.org/", content=stream())
+
+    approximate_request = response.extensions["approximate_raw_request"]
+    assert approximate_request.startswith(b"POST / HTTP/1.1\r\nHost: example.org\r\n")
+    assert approximate_request.endswith(b"Transfer-Encoding: chunked\r\n\r\n0123456")
@@ -4,6 +4,7 @@ Integration tests for authentication.
 Unit tests for auth classes also exist in tests/test_auth.py
 """
 import asyncio
+import base64
 import hashlib
 import os
 import threading
@@ -372,6 +373,24 @@ def test_digest_auth_returns_no_auth_if_alternate_auth_scheme() -> None:
     assert len(response.history) == 0
 
 
+def test_digest_auth_with_several_challenges_in_one_header() -> None:
+    url = "https://example.org/"
+    auth = DigestAuth(username="tomchristie", password="password123")
+
+    def app(request: httpx.Request) -> httpx.Response:
+        if "Authorization" in request.headers:
+            return httpx.Response(200, json={"auth": request.headers["Authorization"]})
+        challenges = 'Basic realm="a, b", Digest realm="httpx@example.org", nonce=abc'
+        return httpx.Response(401, headers={"WWW-Authenticate": challenges})
+
+    client = httpx.Client(transport=httpx.MockTransport(app))
+    response = client.get(url, auth=auth)
+
+    assert response.status_code == 200
+    assert response.json()["auth"].startswith('Digest username="tomchristie"')
+    assert 'realm="httpx@example.org"' in response.json()["auth"]
+
+
 @pytest.mark.asyncio
 async def test_digest_auth_200_response_including_digest_auth_header() -> None:
     url = "https://example.org/"
@@ -697,3 +716,350 @@ def test_sync_auth() -> None:
 
     assert response.status_code == 200
     assert response.json() == {"auth": "sync-auth"}
+
+
+class TokenApp:
+    def __init__(self, token: str, expired_status_code: int = 401) -> None:
+        self.token = token
+        self.expired_status_code = expired_status_code
+
+    def __call__(self, request: httpx.Request) -> httpx.Response:
+        auth = request.headers.get("Authorization")
+        if auth != f"Bearer {self.token}":
+            return httpx.Response(self.expired_status_code)
+        return httpx.Response(200, json={"auth": auth})
+
+
+def test_token_refresh_auth() -> None:
+    url = "https://example.org/"
+    refreshed = []
+
+    def refresh() -> str:
+        refreshed.append(True)
+        return "new-token"
+
+    auth = httpx.TokenRefreshAuth(refresh, token="old-token")
+    app = TokenApp(token="new-token")
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        response = client.get(url)
+        assert response.status_code == 200
+        assert response.json() == {"auth": "Bearer new-token"}
+        assert len(response.history) == 1
+
+        response = client.get(url)
+        assert response.status_code == 200
+        assert len(response.history) == 0
+
+    assert len(refreshed) == 1
+
+
+def test_token_refresh_auth_without_initial_token() -> None:
+    auth = httpx.TokenRefreshAuth(lambda: "token")
+    app = TokenApp(token="token")
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert len(response.history) == 0
+
+
+def test_token_refresh_auth_should_refresh() -> None:
+    auth = httpx.TokenRefreshAuth(
+        lambda: "new-token",
+        token="old-token",
+        should_refresh=lambda response: response.status_code == 403,
+    )
+    app = TokenApp(token="new-token", expired_status_code=403)
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 200
+
+
+def test_token_refresh_auth_refreshes_once() -> None:
+    refreshed = []
+    lock = threading.Lock()
+
+    def refresh() -> str:
+        with lock:
+            refreshed.append(True)
+        return "new-token"
+
+    auth = httpx.TokenRefreshAuth(refresh, token="old-token")
+    app = TokenApp(token="new-token")
+    responses = []
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+
+        def send() -> None:
+            responses.append(client.get("https://example.org/"))
+
+        threads = [threading.Thread(target=send) for _ in range(5)]
+        for thread in threads:
+            thread.start()
+        for thread in threads:
+            thread.join()
+
+    assert [response.status_code for response in responses] == [200] * 5
+    assert len(refreshed) == 1
+
+
+def test_token_refresh_auth_with_async_refresh_in_sync_client() -> None:
+    async def refresh() -> str:
+        return "new-token"  # pragma: nocover
+
+    auth = httpx.TokenRefreshAuth(refresh)
+    app = TokenApp(token="new-token")
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        with pytest.raises(RuntimeError):
+            client.get("https://example.org/")
+
+
+@pytest.mark.asyncio
+async def test_async_token_refresh_auth() -> None:
+    refreshed = []
+
+    async def refresh() -> str:
+        refreshed.append(True)
+        await asyncio.sleep(0.01)
+        return "new-token"
+
+    auth = httpx.TokenRefreshAuth(refresh, token="old-token")
+    app = TokenApp(token="new-token")
+
+    async with httpx.AsyncClient(
+        transport=httpx.MockTransport(app), auth=auth
+    ) as client:
+        responses = await asyncio.gather(
+            *[client.get("https://example.org/") for _ in range(5)]
+        )
+
+    assert [response.status_code for response in responses] == [200] * 5
+    assert len(refreshed) == 1
+
+
+class NegotiateApp:
+    def __init__(self, mutual_authentication: bool = True) -> None:
+        self.mutual_authentication = mutual_authentication
+
+    def __call__(self, request: httpx.Request) -> httpx.Response:
+        auth = request.headers.get("Authorization")
+        if auth == "Negotiate " + base64.b64encode(b"round-1").decode():
+            headers = {"WWW-Authenticate": "Negotiate Y29udGludWU="}  # "continue"
+            return httpx.Response(401, headers=headers)
+        elif auth == "Negotiate " + base64.b64encode(b"round-2").decode():
+            headers = {}
+            if self.mutual_authentication:
+                headers = {"WWW-Authenticate": "Negotiate c2VydmVy"}  # "server"
+            return httpx.Response(200, headers=headers, json={"auth": auth})
+        headers = {"WWW-Authenticate": "Negotiate"}
+        return httpx.Response(401, headers=headers)
+
+
+class SecurityContext:
+    def __init__(self) -> None:
+        self.hosts: typing.List[str] = []
+        self.tokens: typing.List[typing.Optional[bytes]] = []
+
+    def __call__(
+        self, host: str
+    ) -> typing.Callable[[typing.Optional[bytes]], typing.Optional[bytes]]:
+        self.hosts.append(host)
+        return self.step
+
+    def step(self, token: typing.Optional[bytes]) -> typing.Optional[bytes]:
+        self.tokens.append(token)
+        return {None: b"round-1", b"continue": b"round-2"}.get(token)
+
+
+def test_negotiate_auth() -> None:
+    context = SecurityContext()
+    auth = httpx.NegotiateAuth(context)
+    app = NegotiateApp()
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert len(response.history) == 2
+    assert context.hosts == ["example.org"]
+    assert context.tokens == [None, b"continue", b"server"]
+
+
+def test_negotiate_auth_without_challenge() -> None:
+    context = SecurityContext()
+    auth = httpx.NegotiateAuth(context)
+    app = App(auth_header='Basic realm="example"', status_code=401)
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 401
+    assert context.hosts == []
+
+
+def test_negotiate_auth_mutual_authentication_failed() -> None:
+    context = SecurityContext()
+    auth = httpx.NegotiateAuth(context)
+    app = NegotiateApp(mutual_authentication=False)
+
+    with httpx.Client(transport=httpx.MockTransport(app), auth=auth) as client:
+        with pytest.raises(ProtocolError):
+            client.get("https://example.org/")
+
+
+@pytest.mark.asyncio
+async def test_async_negotiate_auth_without_mutual_authentication() -> None:
+    context = SecurityContext()
+    auth = httpx.NegotiateAuth(context, mutual_authentication=False)
+    app = NegotiateApp(mutual_authentication=False)
+
+    async with httpx.AsyncClient(
+        transport=httpx.MockTransport(app), auth=auth
+    ) as client:
+        response = await client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert context.tokens == [None, b"continue"]
+
+
+def ntlm_app(request: httpx.Request) -> httpx.Response:
+    auth = request.headers.get("Authorization", "")
+    message = base64.b64decode(auth[5:]) if auth.startswith("NTLM ") else b""
+    if message[8:12] == b"\x01\x00\x00\x00":
+        challenge = (
+            b"NTLMSSP\x00\x02\x00\x00\x00"
+            + b"\x00" * 8
+            + message[12:16]  # Flags.
+            + b"\x01\x23\x45\x67\x89\xab\xcd\xef"  # Server challenge.
+        )
+        headers = {"WWW-Authenticate": "NTLM " + base64.b64encode(challenge).decode()}
+        return httpx.Response(401, headers=headers)
+    elif message[8:12] == b"\x03\x00\x00\x00":
+        return httpx.Response(200, json={"authenticated": True})
+    return httpx.Response(401, headers={"WWW-Authenticate": "NTLM"})
+
+
+def test_ntlm_auth() -> None:
+    auth = httpx.NTLMAuth("DOMAIN\\user", "password")
+
+    with httpx.Client(transport=httpx.MockTransport(ntlm_app), auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert response.json() == {"authenticated": True}
+    assert len(response.history) == 2
+    assert "connection_affinity" not in response.request.extensions
+
+
+@pytest.mark.asyncio
+async def test_async_ntlm_auth() -> None:
+    auth = httpx.NTLMAuth("user", "password")
+
+    async with httpx.AsyncClient(
+        transport=httpx.MockTransport(ntlm_app), auth=auth
+    ) as client:
+        response = await client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert len(response.history) == 2
+
+
+class PinnedConnection:
+    def __init__(self) -> None:
+        self.is_closed = False
+
+    def close(self) -> None:
+        self.is_closed = True
+
+
+class PinningTransport(httpx.BaseTransport):
+    """
+    Pins a connection to requests sent with the "connection_affinity" extension,
+    in the same way as the default transport.
+    """
+
+    def __init__(self, handler: typing.Callable) -> None:
+        self.handler = handler
+        self.connections: typing.List[PinnedConnection] = []
+        self.released: typing.List[PinnedConnection] = []
+
+    def handle_request(
+        self,
+        method: bytes,
+        url: typing.Tuple[bytes, bytes, typing.Optional[int], bytes],
+        headers: typing.List[typing.Tuple[bytes, bytes]],
+        stream: httpx.SyncByteStream,
+        extensions: dict,
+    ) -> typing.Tuple[
+        int, typing.List[typing.Tuple[bytes, bytes]], httpx.SyncByteStream, dict
+    ]:
+        affinity = extensions.get("connection_affinity")
+        if affinity is not None:
+            if "connection" not in affinity:
+                affinity["connection"] = PinnedConnection()
+                self.connections.append(affinity["connection"])
+            if affinity.get("release"):
+                self.released.append(affinity.pop("connection"))
+
+        request = httpx.Request(method, url, headers=headers, stream=stream)
+        response = self.handler(request)
+        return (
+            response.status_code,
+            response.headers.raw,
+            response.stream,
+            response.extensions,
+        )
+
+
+def test_ntlm_auth_releases_connection() -> None:
+    transport = PinningTransport(ntlm_app)
+    auth = httpx.NTLMAuth("user", "password")
+
+    with httpx.Client(transport=transport, auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 200
+    assert len(transport.connections) == 1
+    assert transport.released == transport.connections
+    assert not transport.connections[0].is_closed
+
+
+def test_ntlm_auth_closes_connection_when_abandoned() -> None:
+    def app(request: httpx.Request) -> httpx.Response:
+        # The server never answers the negotiate message with a challenge.
+        return httpx.Response(401, headers={"WWW-Authenticate": "NTLM"})
+
+    transport = PinningTransport(app)
+    auth = httpx.NTLMAuth("user", "password")
+
+    with httpx.Client(transport=transport, auth=auth) as client:
+        response = client.get("https://example.org/")
+
+    assert response.status_code == 401
+    assert len(transport.connections) == 1
+    assert transport.released == []
+    assert transport.connections[0].is_closed
+
+
+def test_ntlm_auth_closes_connection_on_error() -> None:
+    def app(request: httpx.Request) -> httpx.Response:
+        if request.headers.get("Authorization", "").startswith("NTLM "):
+            message = base64.b64decode(request.headers["Authorization"][5:])
+            if message[8:12] == b"\x01\x00\x00\x00":
+                raise httpx.ReadError("Connection reset", request=request)
+        return ntlm_app(request)
+
+    transport = PinningTransport(app)
+    auth = httpx.NTLMAuth("user", "password")
+
+    with httpx.Client(transport=transport, auth=auth) as client:
+        with pytest.raises(httpx.ReadError):
+            client.get("https://example.org/")
+
+    assert len(transport.connections) == 1
+    assert transport.connections[0].is_closed
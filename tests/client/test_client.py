@@ -1,9 +1,14 @@
+import socket
 import typing
 from datetime import timedelta
 
 import pytest
 
 import httpx
+from httpx._transports.default import build_header_order, order_headers
+
+# A minimal DOS header, pointing to a PE signature at offset 64.
+PE_EXECUTABLE = b"MZ\x90\x00" + b"\x00" * 56 + b"\x40\x00\x00\x00" + b"PE\x00\x00"
 
 
 def test_get(server):
@@ -33,7 +38,9 @@ def test_get(server):
 )
 def test_get_invalid_url(server, url):
     with httpx.Client() as client:
-        with pytest.raises((httpx.UnsupportedProtocol, httpx.LocalProtocolError)):
+        with pytest.raises(
+            (httpx.UnsupportedProtocol, httpx.LocalProtocolError, httpx.InvalidURL)
+        ):
             client.get(url)
 
 
@@ -391,3 +398,276 @@ def test_server_extensions(server):
         response = client.get(url)
     assert response.status_code == 200
     assert response.extensions["http_version"] == b"HTTP/1.1"
+
+
+def test_fixed_source_port(server):
+    with socket.socket() as sock:
+        sock.bind(("127.0.0.1", 0))
+        local_port = sock.getsockname()[1]
+
+    # With `SO_REUSEADDR` set, the same source port may be rebound immediately.
+    url = server.url.copy_with(path="/echo_client")
+    for _ in range(2):
+        transport = httpx.HTTPTransport(local_port=local_port, reuse_address=True)
+        with httpx.Client(transport=transport) as client:
+            response = client.get(url)
+        assert response.status_code == 200
+        assert response.json()["port"] == local_port
+
+
+def test_read_size(server):
+    transport = httpx.HTTPTransport(read_size=4)
+    with httpx.Client(transport=transport) as client:
+        with client.stream("GET", server.url) as response:
+            chunks = list(response.iter_raw())
+    assert all(len(chunk) <= 4 for chunk in chunks)
+    assert b"".join(chunks) == b"Hello, world!"
+
+
+def test_min_chunk_size(server):
+    transport = httpx.HTTPTransport(read_size=4, min_chunk_size=8)
+    with httpx.Client(transport=transport) as client:
+        with client.stream("GET", server.url) as response:
+            chunks = list(response.iter_raw())
+    assert all(len(chunk) >= 8 for chunk in chunks[:-1])
+    assert b"".join(chunks) == b"Hello, world!"
+
+
+def test_failure_cache():
+    with socket.socket() as sock:
+        sock.bind(("127.0.0.1", 0))
+        url = "http://127.0.0.1:%d/" % sock.getsockname()[1]
+
+    clock = httpx.FrozenClock()
+    httpx.set_clock(clock)
+    try:
+        transport = httpx.HTTPTransport(failure_cache_ttl=10.0)
+        with httpx.Client(transport=transport) as client:
+            with pytest.raises(httpx.ConnectError) as exc_info:
+                client.get(url)
+            assert "cached" not in str(exc_info.value)
+
+            with pytest.raises(httpx.ConnectError) as exc_info:
+                client.get(url)
+            assert "cached connection failure" in str(exc_info.value)
+
+            transport.clear_failure_cache(url)
+            with pytest.raises(httpx.ConnectError) as exc_info:
+                client.get(url)
+            assert "cached" not in str(exc_info.value)
+
+            clock.advance(10.0)
+            with pytest.raises(httpx.ConnectError) as exc_info:
+                client.get(url)
+            assert "cached" not in str(exc_info.value)
+    finally:
+        httpx.set_clock(None)
+
+
+def test_order_headers():
+    headers = [
+        (b"Host", b"example.org"),
+        (b"Accept", b"*/*"),
+        (b"X-Custom", b"1"),
+        (b"User-Agent", b"python-httpx"),
+        (b"X-Other", b"2"),
+    ]
+    header_order = build_header_order(["user-agent", "HOST", "Accept"])
+    assert order_headers(headers, header_order) == [
+        (b"user-agent", b"python-httpx"),
+        (b"HOST", b"example.org"),
+        (b"Accept", b"*/*"),
+        (b"X-Custom", b"1"),
+        (b"X-Other", b"2"),
+    ]
+    assert order_headers(headers, build_header_order(None)) == headers
+
+
+def test_preconnect(server):
+    transport = httpx.HTTPTransport()
+    with httpx.Client(transport=transport) as client:
+        client.preconnect(server.url, count=2)
+        connections = [
+            connection
+            for connections in transport._pool._connections.values()
+            for connection in connections
+        ]
+        assert len(connections) == 2
+        assert all(connection.is_idle() for connection in connections)
+
+        response = client.get(server.url)
+    assert response.status_code == 200
+
+
+def test_connection_affinity(server):
+    affinity: typing.Dict[str, typing.Any] = {}
+    with httpx.Client() as client:
+        request = client.build_request("GET", server.url)
+        request.extensions["connection_affinity"] = affinity
+
+        client.send(request)
+        connection = affinity["connection"]
+        client.send(request)
+        assert affinity["connection"] is connection
+
+        # Once released, the connection is returned to the pool.
+        affinity["release"] = True
+        response = client.send(request)
+        assert "connection" not in affinity
+    assert response.status_code == 200
+
+
+def test_preconnect_with_mock_transport():
+    client = httpx.Client(transport=httpx.MockTransport(hello_world))
+    client.preconnect("http://example.com", count=2)
+    client.close()
+
+    with pytest.raises(RuntimeError):
+        client.preconnect("http://example.com")
+
+
+def test_preconnect_through_proxy():
+    with httpx.Client(proxies="http://127.0.0.1:8080") as client:
+        with pytest.warns(UserWarning):
+            client.preconnect("http://example.com")
+
+
+def test_preconnect_with_incompatible_httpcore(monkeypatch):
+    monkeypatch.setattr(
+        "httpx._transports.default.HTTPCORE_CONNECTION_INTERNALS", ("_missing",)
+    )
+    with httpx.Client() as client:
+        with pytest.warns(UserWarning):
+            client.preconnect("http://example.com")
+
+
+def test_connection_affinity_with_incompatible_httpcore(server, monkeypatch):
+    monkeypatch.setattr("httpx._transports.default.sync_connection_class", lambda: None)
+    affinity: typing.Dict[str, typing.Any] = {}
+    with httpx.Client() as client:
+        with pytest.warns(UserWarning):
+            client.preconnect(server.url)
+
+        # Requests are sent through the pool, without pinning a connection.
+        request = client.build_request("GET", server.url)
+        request.extensions["connection_affinity"] = affinity
+        response = client.send(request)
+    assert response.status_code == 200
+    assert affinity == {}
+
+
+def test_content_sniffing():
+    def handler(request: httpx.Request) -> httpx.Response:
+        headers = {"Content-Type": "image/png"}
+        return httpx.Response(200, headers=headers, content=PE_EXECUTABLE)
+
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(transport=transport, content_sniffing="raise") as client:
+        with pytest.raises(httpx.ContentTypeMismatch) as exc_info:
+            client.get("https://example.org/image.png")
+    assert exc_info.value.request.url == "https://example.org/image.png"
+
+    with httpx.Client(transport=transport) as client:
+        response = client.get("https://example.org/image.png")
+        assert response.content == PE_EXECUTABLE
+
+
+def test_conflicting_content_length():
+    def handler(request: httpx.Request) -> httpx.Response:
+        headers = [("Content-Length", "5"), ("Content-Length", "6")]
+        return httpx.Response(200, headers=headers, content=b"Hello")
+
+    with httpx.Client(transport=httpx.MockTransport(handler)) as client:
+        with pytest.raises(httpx.RemoteProtocolError) as exc_info:
+            client.get("https://example.org/")
+    assert exc_info.value.request.url == "https://example.org/"
+
+
+def test_content_length_with_transfer_encoding():
+    def handler(request: httpx.Request) -> httpx.Response:
+        headers = {"Content-Length": "5", "Transfer-Encoding": "chunked"}
+        return httpx.Response(200, headers=headers, content=b"Hello")
+
+    with httpx.Client(transport=httpx.MockTransport(handler)) as client:
+        with pytest.raises(httpx.RemoteProtocolError):
+            client.get("https://example.org/")
+
+
+def test_invalid_response_header():
+    class Transport(httpx.BaseTransport):
+        def handle_request(self, method, url, headers, stream, extensions):
+            headers = [(b"X-A\r\nX-B", b"1")]
+            return 200, headers, httpx.ByteStream(b""), {}
+
+    with httpx.Client(transport=Transport()) as client:
+        with pytest.raises(httpx.RemoteProtocolError):
+            client.get("https://example.org/")
+
+
+def test_invalid_content_sniffing():
+    with pytest.raises(ValueError):
+        httpx.Client(content_sniffing="invalid")
+
+
+def test_capture_approximate_request():
+    transport = httpx.MockTransport(hello_world)
+    with httpx.Client(transport=transport, capture_approximate_request=5) as client:
+        response = client.post("https://example.org/path?a=1", content=b"0123456789")
+
+    assert response.extensions["approximate_raw_request"] == (
+        b"POST /path?a=1 HTTP/1.1\r\n"
+        b"Host: example.org\r\n"
+        b"Accept: */*\r\n"
+        b"Accept-Encoding: " + client.headers["Accept-Encoding"].encode() + b"\r\n"
+        b"Connection: keep-alive\r\n"
+        b"User-Agent: " + client.headers["User-Agent"].encode() + b"\r\n"
+        b"Content-Length: 10\r\n"
+        b"\r\n"
+        b"01234"
+    )
+
+    with httpx.Client(transport=transport, capture_approximate_request=0) as client:
+        response = client.post("https://example.org/", content=b"0123456789")
+    assert response.extensions["approximate_raw_request"].endswith(b"\r\n\r\n")
+
+    with httpx.Client(transport=transport) as client:
+        response = client.get("https://example.org/")
+    assert "approximate_raw_request" not in response.extensions
+
+
+def test_canonical_header_names():
+    def handler(request: httpx.Request) -> httpx.Response:
+        return httpx.Response(200, json=[k.decode() for k, _ in request.headers.raw])
+
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(transport=transport, canonical_header_names=True) as client:
+        response = client.get("https://example.org/", headers={"x-request-id": "1"})
+    assert response.json()[0] == "Host"
+    assert response.json()[-1] == "X-Request-Id"
+
+    with httpx.Client(transport=transport) as client:
+        response = client.get("https://example.org/", headers={"x-request-id": "1"})
+    assert response.json()[-1] == "x-request-id"
+
+    with httpx.Client(
+        transport=transport, canonical_header_names=True, capture_approximate_request=0
+    ) as client:
+        response = client.get("https://example.org/", headers={"x-request-id": "1"})
+    assert b"\r\nX-Request-Id: 1\r\n" in response.extensions["approximate_raw_request"]
+
+
+def test_mmap_uploads(tmp_path):
+    path = tmp_path / "example.txt"
+    path.write_bytes(b"Hello, world!")
+
+    client = httpx.Client(mmap_uploads=True)
+    with path.open("rb") as file:
+        request = client.build_request("POST", "https://example.org/", content=file)
+        chunks = list(request.stream)
+    assert all(isinstance(chunk, memoryview) for chunk in chunks)
+    assert b"".join(chunks) == b"Hello, world!"
+
+
+def test_invalid_capture_approximate_request():
+    with pytest.raises(ValueError):
+        httpx.Client(capture_approximate_request=-1)
@@ -166,3 +166,28 @@ def test_cookie_persistence() -> None:
     response = client.get("http://example.org/echo_cookies")
     assert response.status_code == 200
     assert response.json() == {"cookies": "example-name=example-value"}
+
+
+def test_cookie_fully_qualified_domain_name() -> None:
+    """
+    Cookies set for a host with a trailing dot are sent to the regular host name.
+    """
+    client = httpx.Client(transport=httpx.MockTransport(get_and_set_cookies))
+
+    client.get("http://example.org./set_cookie")
+    response = client.get("http://example.org/echo_cookies")
+    assert response.json() == {"cookies": "example-name=example-value"}
+
+
+def test_scoped_cookies() -> None:
+    scoped_cookies = {"all://*.internal.example.com": {"session": "secret"}}
+    client = httpx.Client(
+        transport=httpx.MockTransport(get_and_set_cookies),
+        scoped_cookies=scoped_cookies,
+    )
+
+    response = client.get("http://api.internal.example.com/echo_cookies")
+    assert response.json() == {"cookies": "session=secret"}
+
+    response = client.get("http://example.org/echo_cookies")
+    assert response.json() == {"cookies": None}
@@ -3,6 +3,7 @@
 import pytest
 
 import httpx
+from httpx._decoders import SUPPORTED_DECODERS
 
 
 def echo_headers(request: httpx.Request) -> httpx.Response:
@@ -77,6 +78,32 @@ def test_header_merge_conflicting_headers():
     }
 
 
+def test_header_merge_policies():
+    url = "http://example.org/echo_headers"
+    client_headers = (
+        httpx.Headers({"Accept": "text/html", "X-Trace": "client"})
+        .with_merge_policy("Accept", "join")
+        .with_merge_policy("X-Trace", "append")
+    )
+    client = httpx.Client(headers=client_headers)
+    request_headers = {"Accept": "application/json", "X-Trace": "request"}
+    request = client.build_request("GET", url, headers=request_headers)
+
+    assert request.headers.get_list("Accept") == ["text/html, application/json"]
+    assert request.headers.get_list("X-Trace") == ["client", "request"]
+    assert request.headers["User-Agent"] == f"python-httpx/{httpx.__version__}"
+
+
+def test_header_removed_for_single_request():
+    client = httpx.Client(headers={"X-Client": "1"})
+    request = client.build_request(
+        "GET", "http://example.org/", headers={"User-Agent": None, "X-Client": ""}
+    )
+    assert "User-Agent" not in request.headers
+    assert request.headers["X-Client"] == ""
+    assert "User-Agent" in client.headers
+
+
 def test_header_update():
     url = "http://example.org/echo_headers"
     client = httpx.Client(transport=httpx.MockTransport(echo_headers))
@@ -188,3 +215,64 @@ def test_host_with_non_default_port_in_url():
 def test_request_auto_headers():
     request = httpx.Request("GET", "https://www.example.org/")
     assert "host" in request.headers
+
+
+def test_scoped_headers():
+    scoped_headers = {
+        "all://*.internal.example.com": {"X-API-Key": "secret"},
+        "all://admin.internal.example.com": {"X-API-Key": "admin-secret"},
+    }
+    client = httpx.Client(
+        transport=httpx.MockTransport(echo_headers), scoped_headers=scoped_headers
+    )
+
+    response = client.get("https://api.internal.example.com/echo_headers")
+    assert response.json()["headers"]["x-api-key"] == "secret"
+
+    response = client.get("https://admin.internal.example.com/echo_headers")
+    assert response.json()["headers"]["x-api-key"] == "admin-secret"
+
+    response = client.get("https://example.org/echo_headers")
+    assert "x-api-key" not in response.json()["headers"]
+
+
+def test_scoped_headers_stripped_on_redirect():
+    def redirect_to_example_org(request: httpx.Request) -> httpx.Response:
+        if request.url.host == "api.internal.example.com":
+            headers = {"Location": "https://example.org/echo_headers"}
+            return httpx.Response(302, headers=headers)
+        return echo_headers(request)
+
+    client = httpx.Client(
+        transport=httpx.MockTransport(redirect_to_example_org),
+        scoped_headers={"all://*.internal.example.com": {"X-API-Key": "secret"}},
+    )
+    response = client.get("https://api.internal.example.com/")
+    assert response.url == "https://example.org/echo_headers"
+    assert "x-api-key" not in response.json()["headers"]
+
+
+def test_explicit_headers_kept_on_redirect_with_scoped_headers():
+    def redirect_to_example_org(request: httpx.Request) -> httpx.Response:
+        if request.url.host == "api.internal.example.com":
+            headers = {"Location": "https://example.org/echo_headers"}
+            return httpx.Response(302, headers=headers)
+        return echo_headers(request)
+
+    client = httpx.Client(
+        transport=httpx.MockTransport(redirect_to_example_org),
+        scoped_headers={"all://*.internal.example.com": {"X-API-Key": "secret"}},
+    )
+    headers = {"X-API-Key": "public"}
+    response = client.get("https://api.internal.example.com/", headers=headers)
+    assert response.url == "https://example.org/echo_headers"
+    assert response.json()["headers"]["x-api-key"] == "public"
+
+
+def test_accept_encoding_matches_supported_decoders():
+    """
+    The default Accept-Encoding header should only list encodings we can decode.
+    """
+    client = httpx.Client()
+    accept_encoding = client.headers["Accept-Encoding"].split(", ")
+    assert set(accept_encoding) == set(SUPPORTED_DECODERS) - {"identity"}
@@ -33,3 +33,44 @@ def test_client_queryparams_echo():
 
     assert response.status_code == 200
     assert response.url == "http://example.org/echo_queryparams?first=str&second=dict"
+
+
+def test_client_queryparams_exclude():
+    url = "http://example.org/echo_queryparams"
+    client = httpx.Client(
+        transport=httpx.MockTransport(hello_world),
+        params={"format": "json", "key": "abc"},
+    )
+
+    response = client.get(url, params=httpx.QueryParams(page=2).exclude("format"))
+    assert response.url == "http://example.org/echo_queryparams?key=abc&page=2"
+
+    response = client.get(url)
+    assert response.url == "http://example.org/echo_queryparams?format=json&key=abc"
+
+
+def test_client_value_converters():
+    class Model:
+        def __init__(self, pk):
+            self.pk = pk
+
+    class SubModel(Model):
+        pass
+
+    client = httpx.Client(
+        params={"owner": Model(1)},
+        value_converters={Model: lambda model: str(model.pk)},
+    )
+    request = client.build_request(
+        "POST",
+        "http://example.org/",
+        params={"id": [Model(2), SubModel(3)]},
+        data={"id": SubModel(4)},
+    )
+    assert request.url == "http://example.org/?owner=1&id=2&id=3"
+    assert request.read() == b"id=4"
+
+    # Converters only apply to the client they are given to.
+    client = httpx.Client()
+    request = client.build_request("GET", "http://example.org/", params={"id": Model(1)})
+    assert request.url.params["id"].startswith("<")
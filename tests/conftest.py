@@ -82,6 +82,8 @@ async def app(scope, receive, send):
         await echo_body(scope, receive, send)
     elif scope["path"].startswith("/echo_headers"):
         await echo_headers(scope, receive, send)
+    elif scope["path"].startswith("/echo_client"):
+        await echo_client(scope, receive, send)
     elif scope["path"].startswith("/redirect_301"):
         await redirect_301(scope, receive, send)
     else:
@@ -157,6 +159,20 @@ async def echo_headers(scope, receive, send):
     await send({"type": "http.response.body", "body": json.dumps(body).encode()})
 
 
+async def echo_client(scope, receive, send):
+    host, port = scope["client"]
+    body = {"host": host, "port": port}
+
+    await send(
+        {
+            "type": "http.response.start",
+            "status": 200,
+            "headers": [[b"content-type", b"application/json"]],
+        }
+    )
+    await send({"type": "http.response.body", "body": json.dumps(body).encode()})
+
+
 async def redirect_301(scope, receive, send):
     await send(
         {"type": "http.response.start", "status": 301, "headers": [[b"location", b"/"]]}
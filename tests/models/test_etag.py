@@ -0,0 +1,51 @@
+import pytest
+
+import httpx
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ('"abc"', httpx.ETag("abc")),
+        ('W/"abc"', httpx.ETag("abc", weak=True)),
+        (' w/"abc" ', httpx.ETag("abc", weak=True)),
+        ("abc", httpx.ETag("abc")),
+        ('""abc""', None),
+        ('""', None),
+        ("W/", None),
+        ("*", None),
+        ('"*"', httpx.ETag("*")),
+    ],
+)
+def test_etag_parse(value, expected):
+    if expected is None:
+        with pytest.raises(ValueError):
+            httpx.ETag.parse(value)
+    else:
+        assert httpx.ETag.parse(value) == expected
+
+
+def test_etag_str():
+    assert str(httpx.ETag("abc")) == '"abc"'
+    assert str(httpx.ETag("abc", weak=True)) == 'W/"abc"'
+
+
+@pytest.mark.parametrize(
+    "first,second,strong,weak",
+    [
+        ('W/"1"', 'W/"1"', False, True),
+        ('W/"1"', 'W/"2"', False, False),
+        ('W/"1"', '"1"', False, True),
+        ('"1"', '"1"', True, True),
+    ],
+)
+def test_etag_comparison(first, second, strong, weak):
+    etag = httpx.ETag.parse(first)
+    assert etag.strong_match(second) is strong
+    assert etag.weak_match(httpx.ETag.parse(second)) is weak
+
+
+def test_etag_list():
+    etags = httpx.ETag.parse_list('"a", W/"b,c", *, "d""')
+    assert etags == [httpx.ETag("a"), httpx.ETag("b,c", weak=True)]
+    assert httpx.ETag.join(etags + ['"e"', "f"]) == '"a", W/"b,c", "e", "f"'
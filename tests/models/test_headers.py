@@ -1,3 +1,6 @@
+import copy
+import pickle
+
 import pytest
 
 import httpx
@@ -50,6 +53,19 @@ def test_header_mutations():
     assert h.raw == [(b"b", b"4")]
 
 
+def test_header_mutations_are_case_insensitive():
+    h = httpx.Headers([("X-A", "1"), ("x-a", "2"), ("X-B", "3")])
+    h["X-a"] = "4"
+    assert h.multi_items() == [("x-a", "4"), ("x-b", "3")]
+    assert h.setdefault("X-b", "5") == "3"
+    assert h.pop("X-B") == "3"
+    assert h.pop("X-B", None) is None
+    h.update({"X-A": "6", "X-C": "7"})
+    assert h.raw == [(b"X-A", b"6"), (b"X-C", b"7")]
+    del h["x-c"]
+    assert h.raw == [(b"X-A", b"6")]
+
+
 def test_copy_headers_method():
     headers = httpx.Headers({"custom": "example"})
     headers_copy = headers.copy()
@@ -63,6 +79,27 @@ def test_copy_headers_init():
     assert headers == headers_copy
 
 
+@pytest.mark.parametrize(
+    "duplicate",
+    [
+        copy.copy,
+        copy.deepcopy,
+        lambda headers: pickle.loads(pickle.dumps(headers)),
+    ],
+)
+def test_duplicate_headers(duplicate):
+    headers = httpx.Headers([("A", "1"), ("a", "2")], encoding="utf-8")
+    headers = headers.with_merge_policy("A", "append")
+    duplicated = duplicate(headers)
+    assert duplicated == headers
+    assert duplicated.raw == headers.raw
+    assert duplicated.encoding == "utf-8"
+    assert duplicated.merge({"A": "3"}).get_list("A") == ["1", "2", "3"]
+
+    duplicated["B"] = "4"
+    assert "B" not in headers
+
+
 def test_headers_insert_retains_ordering():
     headers = httpx.Headers({"a": "a", "b": "b", "c": "c"})
     headers["b"] = "123"
@@ -166,7 +203,56 @@ def test_multiple_headers():
     assert h.get_list("Vary", split_commas=True) == ["a", "b", "c"]
 
 
-@pytest.mark.parametrize("header", ["authorization", "proxy-authorization"])
+@pytest.mark.parametrize(
+    "header,value,expected",
+    [
+        ("Vary", "a,b ,, c", ["a", "b", "c"]),
+        (
+            "Cache-Control",
+            'no-cache="a, b", max-age=0',
+            ['no-cache="a, b"', "max-age=0"],
+        ),
+        ("X-Quoted", r'"a\", b", c', [r'"a\", b"', "c"]),
+        ("Expires", "Thu, 01 Dec 1994 16:00:00 GMT", ["Thu, 01 Dec 1994 16:00:00 GMT"]),
+        ("Set-Cookie", "a=1; Expires=Thu, 01 Dec", ["a=1; Expires=Thu, 01 Dec"]),
+    ],
+)
+def test_get_list_split_commas(header, value, expected):
+    h = httpx.Headers({header: value})
+    assert h.get_list(header, split_commas=True) == expected
+
+
+def test_set_cookie_headers_are_split():
+    """
+    Comma joined `Set-Cookie` values given in a mapping or by assignment are
+    stored as separate header lines, without splitting any `Expires` dates.
+    Lists of header lines, such as those received from a server, are kept
+    exactly as given.
+    """
+    value = "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Path=/, b=2"
+    h = httpx.Headers({"Set-Cookie": value})
+    assert h.raw == [
+        (b"Set-Cookie", b"a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Path=/"),
+        (b"Set-Cookie", b"b=2"),
+    ]
+
+    h["Set-Cookie"] = "c=3, d=4"
+    assert h.get_list("Set-Cookie") == ["c=3", "d=4"]
+
+    h = httpx.Headers({"Set-Cookie": "a=1"}).with_merge_policy("Set-Cookie", "join")
+    h = h.merge({"Set-Cookie": "b=2"})
+    assert h.get_list("Set-Cookie") == ["a=1", "b=2"]
+
+    h = httpx.Headers({}).merge({"Set-Cookie": "c=3, d=4"})
+    assert h.get_list("Set-Cookie") == ["c=3", "d=4"]
+
+    h = httpx.Headers([("Set-Cookie", "a=1;  Path=/,b=2")])
+    assert h.raw == [(b"Set-Cookie", b"a=1;  Path=/,b=2")]
+
+
+@pytest.mark.parametrize(
+    "header", ["authorization", "proxy-authorization", "cookie", "set-cookie"]
+)
 def test_sensitive_headers(header):
     """
     Some headers should be obfuscated because they contain sensitive data.
@@ -174,3 +260,183 @@ def test_sensitive_headers(header):
     value = "s3kr3t"
     h = httpx.Headers({header: value})
     assert repr(h) == "Headers({'%s': '[secure]'})" % header
+
+
+def test_headers_with_sensitive():
+    headers = httpx.Headers({"X-Api-Key": "s3kr3t"}).with_sensitive("X-API-Key")
+    assert repr(headers) == "Headers({'x-api-key': '[secure]'})"
+    assert repr(headers.copy()) == "Headers({'x-api-key': '[secure]'})"
+    merged = httpx.Headers({"Accept": "*/*"}).merge(headers)
+    assert repr(merged) == "Headers({'accept': '*/*', 'x-api-key': '[secure]'})"
+
+    # The setting only applies to the instance it is given to, and its copies.
+    assert repr(httpx.Headers({"X-Api-Key": "s3kr3t"})) == (
+        "Headers({'x-api-key': 's3kr3t'})"
+    )
+
+    headers = httpx.Headers({"Authorization": "s3kr3t"})
+    headers = headers.with_sensitive("Authorization", sensitive=False)
+    assert repr(headers) == "Headers({'authorization': 's3kr3t'})"
+
+
+def test_headers_merge_replace():
+    headers = httpx.Headers([("a", "1"), ("b", "2"), ("a", "3")])
+    merged = headers.merge({"A": "4"})
+    assert merged.raw == [(b"A", b"4"), (b"b", b"2")]
+    assert headers.raw == [(b"a", b"1"), (b"b", b"2"), (b"a", b"3")]
+    merged = headers.merge([("b", "5"), ("c", "6"), ("B", "7"), ("c", "8")])
+    assert merged.raw == [(b"a", b"1"), (b"B", b"7"), (b"a", b"3"), (b"c", b"8")]
+
+
+def test_headers_merge_append():
+    headers = httpx.Headers({"Via": "1.1 a"}).with_merge_policy("via", "append")
+    merged = headers.merge([("Via", "1.1 b"), ("Via", "1.1 a")])
+    assert merged.get_list("via") == ["1.1 a", "1.1 b"]
+    assert merged.merge({"Via": "1.1 b"}) == merged
+
+
+def test_headers_merge_join():
+    headers = httpx.Headers({"Accept": "text/html, text/plain"})
+    headers = headers.with_merge_policy("Accept", "join")
+    merged = headers.merge({"Accept": "text/plain, application/json"})
+    assert merged.raw == [(b"Accept", b"text/html, text/plain, application/json")]
+    assert merged.merge({"Accept": "application/json"}) == merged
+
+
+def test_headers_merge_join_quoted_commas():
+    headers = httpx.Headers({"Warning": '199 - "a, b"'})
+    headers = headers.with_merge_policy("Warning", "join")
+    merged = headers.merge({"Warning": '199 - "c, d", 199 - "a, b"'})
+    assert merged["Warning"] == '199 - "a, b", 199 - "c, d"'
+
+
+def test_headers_merge_policy_precedence():
+    headers = httpx.Headers({"Accept": "text/html"}).with_merge_policy(
+        "Accept", "join"
+    )
+    other = httpx.Headers({"Accept": "text/plain"}).with_merge_policy(
+        "Accept", "replace"
+    )
+    assert headers.merge(other)["Accept"] == "text/plain"
+    assert headers.copy().merge({"Accept": "*/*"})["Accept"] == "text/html, */*"
+
+
+def test_headers_invalid_merge_policy():
+    with pytest.raises(ValueError, match="Invalid merge policy"):
+        httpx.Headers().with_merge_policy("Accept", "prepend")
+
+
+def test_headers_merge_removes_none():
+    headers = httpx.Headers([("Accept", "*/*"), ("X-A", "1"), ("X-B", "2")])
+    merged = headers.merge({"accept": None, "X-B": "", "X-C": "3"})
+    assert merged.raw == [(b"X-A", b"1"), (b"X-B", b""), (b"X-C", b"3")]
+
+    merged = headers.merge(httpx.Headers({"X-B": "", "X-C": "3"}))
+    assert merged.raw == [
+        (b"Accept", b"*/*"),
+        (b"X-A", b"1"),
+        (b"X-B", b""),
+        (b"X-C", b"3"),
+    ]
+
+    merged = headers.merge([(b"x-a", None), (b"X-A", b"4")])
+    assert merged.raw == [(b"Accept", b"*/*"), (b"X-B", b"2"), (b"X-A", b"4")]
+
+
+@pytest.mark.parametrize(
+    "key,value",
+    [
+        ("X-A\r\nX-B", "1"),
+        ("X A", "1"),
+        ("X:A", "1"),
+        ("", "1"),
+        ("X-A", "1\r\nX-B: 2"),
+        ("X-A", "1\n"),
+        ("X-A", "1\x00"),
+        (b"X-A", b"1\rX-B: 2"),
+    ],
+)
+def test_headers_reject_invalid_name_or_value(key, value):
+    with pytest.raises(httpx.LocalProtocolError):
+        httpx.Headers({key: value})
+    with pytest.raises(httpx.LocalProtocolError):
+        httpx.Headers([(key, value)])
+
+    headers = httpx.Headers()
+    with pytest.raises(httpx.LocalProtocolError):
+        headers[key] = value
+    assert headers.raw == []
+
+
+def test_headers_allow_valid_names_and_values():
+    headers = httpx.Headers({"X-A_b.c~!#$%&'*+^`|": b"tab\tand obs-text \xe9"})
+    assert headers.raw == [(b"X-A_b.c~!#$%&'*+^`|", b"tab\tand obs-text \xe9")]
+
+
+def test_headers_bytes():
+    headers = httpx.Headers([("Host", "example.org"), ("X-Name", "caf\xe9")], "latin-1")
+    headers["X-Name"] = "\xe9t\xe9"
+    headers = headers.merge([(b"X-Other", b"\xc3\xa9")])
+    assert bytes(headers) == (
+        b"Host: example.org\r\nX-Name: \xe9t\xe9\r\nX-Other: \xc3\xa9\r\n"
+    )
+    assert bytes(httpx.Headers()) == b""
+
+
+def test_headers_canonical_raw():
+    headers = httpx.Headers([("content-TYPE", "text/plain"), ("x-request-id", "1")])
+    assert headers.canonical_raw == [
+        (b"Content-Type", b"text/plain"),
+        (b"X-Request-Id", b"1"),
+    ]
+    assert headers.raw == [(b"content-TYPE", b"text/plain"), (b"x-request-id", b"1")]
+    assert headers["Content-Type"] == "text/plain"
+    assert headers.to_bytes() == bytes(headers)
+    assert headers.to_bytes(canonical=True) == (
+        b"Content-Type: text/plain\r\nX-Request-Id: 1\r\n"
+    )
+
+
+def test_headers_from_raw():
+    data = (
+        b"Content-Type: text/plain\r\n"
+        b"Set-Cookie: a=1\r\n"
+        b"set-cookie:b=2  \r\n"
+        b"X-Folded: first\r\n"
+        b"  second\r\n"
+        b"\tthird\n"
+        b"\r\n"
+        b"Ignored: after the end\r\n"
+    )
+    headers = httpx.Headers.from_raw(data)
+    assert headers.raw == [
+        (b"Content-Type", b"text/plain"),
+        (b"Set-Cookie", b"a=1"),
+        (b"set-cookie", b"b=2"),
+        (b"X-Folded", b"first second third"),
+    ]
+    assert headers.get_list("set-cookie") == ["a=1", "b=2"]
+    assert bytes(httpx.Headers.from_raw(bytes(headers))) == bytes(headers)
+
+
+@pytest.mark.parametrize(
+    "data",
+    [
+        b" folded: first line\r\n",
+        b"No colon\r\n",
+        b"Bad Name: value\r\n",
+        b"Value: with\x00nul\r\n",
+        b"X-Long: " + b"a" * 100 + b"\r\n",
+        b"X-A: 1\r\n" * 20,
+        b"X-Folded: " + b"a" * 60 + b"\r\n " + b"b" * 60 + b"\r\n",
+    ],
+)
+def test_headers_from_raw_invalid(data):
+    with pytest.raises(httpx.RemoteProtocolError):
+        httpx.Headers.from_raw(data, max_line_size=100, max_size=150)
+
+
+def test_headers_from_raw_max_size_excludes_body():
+    data = b"X-A: 1\r\n\r\n" + b"body" * 100
+    headers = httpx.Headers.from_raw(data, max_size=10)
+    assert headers.raw == [(b"X-A", b"1")]
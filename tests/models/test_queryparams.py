@@ -1,3 +1,9 @@
+import copy
+import pickle
+import types
+import typing
+from datetime import datetime, timedelta, timezone
+
 import pytest
 
 import httpx
@@ -114,6 +120,85 @@ def test_queryparam_merge():
     assert q == httpx.QueryParams("a=000&b=456&c=789")
 
 
+def test_queryparam_space_encoding():
+    q = httpx.QueryParams({"a": "1 2", "b": "x/y"})
+    assert str(q) == "a=1+2&b=x%2Fy"
+
+    q = q.with_space_encoding("%20")
+    assert str(q) == "a=1%202&b=x%2Fy"
+    assert str(q.set("c", "3 4")) == "a=1%202&b=x%2Fy&c=3%204"
+    assert str(q.merge({"a": "5 6"})) == "a=5%206&b=x%2Fy"
+    assert str(httpx.QueryParams().merge(q)) == "a=1%202&b=x%2Fy"
+    assert q == httpx.QueryParams("a=1+2&b=x/y")
+
+    with pytest.raises(ValueError):
+        q.with_space_encoding(" ")
+
+
+def test_queryparam_plus_round_trip():
+    q = httpx.QueryParams("a=x+y&b=x%2By&c=x%20y")
+    assert q.multi_items() == [("a", "x y"), ("b", "x+y"), ("c", "x y")]
+    assert httpx.QueryParams(str(q)) == q
+    assert httpx.QueryParams(str(q.with_space_encoding("%20"))) == q
+
+
+def test_queryparams_exclude():
+    q = httpx.QueryParams("a=1&b=2&c=3").exclude("b", "c")
+    assert q == httpx.QueryParams("a=1")
+
+    merged = httpx.QueryParams("b=4&d=5").merge(q)
+    assert merged == httpx.QueryParams("d=5&a=1")
+
+    q = httpx.QueryParams().exclude("b").set("b", "6")
+    assert httpx.QueryParams("b=4&d=5").merge(q) == httpx.QueryParams("b=6&d=5")
+
+
+def test_queryparams_separators():
+    q = httpx.QueryParams.parse("a=1;b=2&c=3", separators="&;")
+    assert q.multi_items() == [("a", "1"), ("b", "2"), ("c", "3")]
+    assert str(q) == "a=1&b=2&c=3"
+
+    q = httpx.QueryParams.parse("a=1;b=x&y", separators=";")
+    assert q.multi_items() == [("a", "1"), ("b", "x&y")]
+
+    q = httpx.QueryParams("a=1;b=2")
+    assert q.multi_items() == [("a", "1;b=2")]
+
+    with pytest.raises(ValueError):
+        httpx.QueryParams.parse("a=1", separators=",")
+
+
+def test_queryparams_keep_blank_values():
+    q = httpx.QueryParams("flag&empty=&a=1")
+    assert q.multi_items() == [("a", "1")]
+
+    q = httpx.QueryParams.parse("flag&empty=&a=1", keep_blank_values=True)
+    assert q.multi_items() == [("flag", ""), ("empty", ""), ("a", "1")]
+    assert str(q) == "flag&empty=&a=1"
+    assert str(q.set("flag", "")) == "flag=&empty=&a=1"
+    assert str(q.merge({"b": "2"})) == "flag&empty=&a=1&b=2"
+
+
+def test_url_params_blank_values():
+    url = httpx.URL("https://example.org/?flag&empty=&a=1")
+    assert url.params == httpx.QueryParams("a=1")
+
+
+def test_queryparams_option_names_as_keys():
+    q = httpx.QueryParams(separators=",", errors="x", nested=1, max_params=2)
+    assert str(q) == "separators=%2C&errors=x&nested=1&max_params=2"
+
+
+def test_queryparam_space_encoding_in_url():
+    params = httpx.QueryParams({"search": "a b"}).with_space_encoding("%20")
+    url = httpx.URL("https://example.org/?page=1")
+    assert url.copy_merge_params(params) == "https://example.org/?page=1&search=a%20b"
+
+    client = httpx.Client(params=httpx.QueryParams().with_space_encoding("%20"))
+    request = client.build_request("GET", "https://example.org/", params={"q": "a b"})
+    assert request.url == "https://example.org/?q=a%20b"
+
+
 def test_queryparams_are_hashable():
     params = (
         httpx.QueryParams("a=123"),
@@ -123,3 +208,445 @@ def test_queryparams_are_hashable():
     )
 
     assert len(set(params)) == 2
+
+
+def test_queryparams_views():
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert isinstance(q.values(), typing.ValuesView)
+    assert isinstance(q.items(), typing.ItemsView)
+    assert "789" in q.values()
+    assert ("a", "123") in q.items()
+    assert ("a", "456") not in q.items()
+    assert len(q.values()) == len(q.items()) == 2
+
+
+def test_queryparams_remove_value():
+    q = httpx.QueryParams("a=123&a=456&b=789&a=123&c=true")
+    assert q.remove_value("a", "123") == httpx.QueryParams("a=456&b=789&c=true")
+    assert q.remove_value("b", "789") == httpx.QueryParams("a=123&a=456&a=123&c=true")
+    assert q.remove_value("c", True) == httpx.QueryParams("a=123&a=456&b=789&a=123")
+    assert q.remove_value("a", "000") == q
+    assert q.remove_value("d", "1") == q
+
+
+def test_queryparams_merge_extend():
+    q = httpx.QueryParams("id=1&sort=name")
+    assert q.merge({"id": ["2", "3"]}, extend=True) == httpx.QueryParams(
+        "id=1&id=2&id=3&sort=name"
+    )
+    assert q.merge({"id": "2", "sort": "date"}, extend=["id"]).multi_items() == [
+        ("id", "1"),
+        ("id", "2"),
+        ("sort", "date"),
+    ]
+    assert q.merge({"id": "2"}, extend="id") == httpx.QueryParams("id=1&id=2&sort=name")
+    assert q.merge({"id": "2"}) == httpx.QueryParams("id=2&sort=name")
+    assert q.merge({"page": "2"}, extend=True) == httpx.QueryParams(
+        "id=1&sort=name&page=2"
+    )
+
+
+def test_queryparams_from_mapping_like_objects():
+    class MultiDict:
+        def __init__(self, items):
+            self._items = items
+
+        def items(self):
+            return list(self._items)
+
+    class KeysOnly:
+        def keys(self):
+            return ["a", "b"]
+
+        def __getitem__(self, key):
+            return {"a": "123", "b": ["456", "789"]}[key]
+
+    q = httpx.QueryParams(MultiDict([("a", "123"), ("b", "456"), ("a", "789")]))
+    assert q.multi_items() == [("a", "123"), ("a", "789"), ("b", "456")]
+
+    q = httpx.QueryParams(KeysOnly())
+    assert q.multi_items() == [("a", "123"), ("b", "456"), ("b", "789")]
+
+    q = httpx.QueryParams(types.MappingProxyType({"a": 1, "b": [True, None]}))
+    assert str(q) == "a=1&b=true&b="
+
+
+def test_queryparams_get_all():
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert q.get_all("a") == ("123", "456")
+    assert q.get_all("c") == ()
+
+
+def test_queryparams_set_list():
+    q = httpx.QueryParams("a=123&b=456&a=789")
+    assert q.set_list("a", ["1", 2, True]).multi_items() == [
+        ("a", "1"),
+        ("a", "2"),
+        ("a", "true"),
+        ("b", "456"),
+    ]
+    assert q.set_list("c", ["1"]) == httpx.QueryParams("a=123&a=789&b=456&c=1")
+    assert q.set_list("a", []) == httpx.QueryParams("b=456")
+    assert q.get_list("a") == ["123", "789"]
+
+
+def test_queryparams_get_int():
+    q = httpx.QueryParams("page=2&page=3&limit=ten&offset=-5")
+    assert q.get_int("page") == 2
+    assert q.get_int("offset") == -5
+    assert q.get_int("missing") is None
+    assert q.get_int("missing", default=100) == 100
+    with pytest.raises(ValueError, match="Invalid integer for query parameter"):
+        q.get_int("limit")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [("1", True), ("TRUE", True), ("yes", True), ("0", False), ("False", False)],
+)
+def test_queryparams_get_bool(value, expected):
+    q = httpx.QueryParams({"flag": value})
+    assert q.get_bool("flag") is expected
+
+
+def test_queryparams_get_bool_invalid():
+    q = httpx.QueryParams("flag=maybe")
+    assert q.get_bool("missing") is None
+    assert q.get_bool("missing", default=False) is False
+    with pytest.raises(ValueError, match="Invalid boolean for query parameter"):
+        q.get_bool("flag")
+
+
+@pytest.mark.parametrize(
+    "value,expected",
+    [
+        ("2021-05-01", datetime(2021, 5, 1)),
+        ("2021-05-01T12:30", datetime(2021, 5, 1, 12, 30)),
+        ("2021-05-01T12:30:15.5", datetime(2021, 5, 1, 12, 30, 15, 500000)),
+        ("2021-05-01T12:30:15Z", datetime(2021, 5, 1, 12, 30, 15, tzinfo=timezone.utc)),
+        (
+            "2021-05-01 12:30:15+05:30",
+            datetime(2021, 5, 1, 12, 30, 15, tzinfo=timezone(timedelta(hours=5.5))),
+        ),
+    ],
+)
+def test_queryparams_get_datetime(value, expected):
+    q = httpx.QueryParams({"since": value})
+    assert q.get_datetime("since") == expected
+
+
+def test_queryparams_get_datetime_with_format():
+    q = httpx.QueryParams("since=01/06/2021&until=2021-06-01")
+    assert q.get_datetime("since", format="%d/%m/%Y") == datetime(2021, 6, 1)
+    assert q.get_datetime("missing") is None
+    with pytest.raises(ValueError, match="Invalid datetime for query parameter"):
+        q.get_datetime("until", format="%d/%m/%Y")
+    with pytest.raises(ValueError, match="Invalid datetime for query parameter"):
+        q.get_datetime("since")
+
+
+def test_queryparams_sorted():
+    q = httpx.QueryParams("b=2&a=3&a=1").with_space_encoding("%20")
+    assert str(q.sorted()) == "a=1&a=3&b=2"
+    assert q.sorted() == q
+    assert str(q.set("c", "x y").sorted()) == "a=1&a=3&b=2&c=x%20y"
+
+
+def test_queryparams_sorted_is_canonical():
+    first = httpx.QueryParams([("b", "2"), ("a", "z"), ("a", "y"), ("c", "")])
+    second = httpx.QueryParams([("c", ""), ("a", "y"), ("b", "2"), ("a", "z")])
+    assert str(first) != str(second)
+    assert str(first.sorted()) == str(second.sorted()) == "a=y&a=z&b=2&c="
+    assert first.sorted().sorted().multi_items() == first.sorted().multi_items()
+
+
+def test_queryparams_non_utf8_round_trip():
+    q = httpx.QueryParams(b"a=%E9t%E9&b=caf\xe9&c=%C3%A9")
+    assert q["a"] == "\udce9t\udce9"
+    assert q["c"] == "\u00e9"
+    assert str(q) == "a=%E9t%E9&b=caf%E9&c=%C3%A9"
+    assert bytes(q) == b"a=%E9t%E9&b=caf%E9&c=%C3%A9"
+    assert httpx.QueryParams(bytes(q)) == q
+
+
+def test_queryparams_non_utf8_errors():
+    q = httpx.QueryParams.parse(b"a=%E9t%E9&b=caf\xe9", errors="replace")
+    assert q["a"] == "\ufffdt\ufffd"
+    assert q["b"] == "caf\ufffd"
+    assert str(q) == "a=%EF%BF%BDt%EF%BF%BD&b=caf%EF%BF%BD"
+
+    with pytest.raises(UnicodeDecodeError):
+        httpx.QueryParams.parse("a=%E9", errors="strict")
+    with pytest.raises(UnicodeDecodeError):
+        httpx.QueryParams.parse(b"b=caf\xe9", errors="strict")
+    assert httpx.QueryParams.parse("a=%C3%A9", errors="strict")["a"] == "\u00e9"
+
+    with pytest.raises(ValueError):
+        httpx.QueryParams.parse("a=1", errors="ignore")
+
+
+def test_url_params_non_utf8_round_trip():
+    url = httpx.URL("https://example.org/?q=%E9")
+    assert url.copy_merge_params({"page": "2"}) == "https://example.org/?q=%E9&page=2"
+
+
+def test_queryparams_pickleable():
+    q = httpx.QueryParams.parse("flag&a=1%202", keep_blank_values=True)
+    q = q.with_space_encoding("%20").exclude("b")
+
+    for copied in (pickle.loads(pickle.dumps(q)), copy.deepcopy(q)):
+        assert copied == q
+        assert str(copied) == "flag&a=1%202"
+        assert copied.merge({"b": "2"}) == q.merge({"b": "2"})
+
+
+def test_queryparams_derived_instances_share_values():
+    values = {f"k{i}": str(i) for i in range(100)}
+    base = httpx.QueryParams(values)
+    q = base.set("k1", "x").add("page", 2).remove("k2")
+    assert q._dict.parent.parent.parent is base._dict
+    assert q.multi_items()[:3] == [("k0", "0"), ("k1", "x"), ("k3", "3")]
+    assert q.multi_items()[-1] == ("page", "2")
+    assert len(q) == 100
+    assert base == values
+
+    # As with dicts, a key that is removed and added again is moved to the end.
+    q = q.remove("k0").add("k0", "y")
+    assert list(q)[-2:] == ["page", "k0"]
+    assert q.merge({"k1": "z", "new": "1"}, extend=["k1"]).get_list("k1") == ["x", "z"]
+
+    # Long chains of changes are copied, so that lookups stay fast.
+    for i in range(20):
+        q = q.set("page", i)
+        assert getattr(q._dict, "depth", 0) <= 8
+    assert q["page"] == "19"
+    assert list(q)[-2:] == ["page", "k0"]
+    assert pickle.loads(pickle.dumps(q)) == q
+
+
+def test_queryparams_large_query_string():
+    pairs = [f"k{i % 100}=v+{i}%21" for i in range(100_000)]
+    query = "&".join(pairs + ["", "flag", "empty=", "=x"])
+
+    q = httpx.QueryParams(query)
+    assert len(q.get_list("k0")) == 1000
+    assert q.get_list("k0")[:2] == ["v 0!", "v 100!"]
+    assert "flag" not in q
+    assert "empty" not in q
+    assert q[""] == "x"
+
+    q = httpx.QueryParams.parse(query, keep_blank_values=True)
+    assert q["flag"] == ""
+    assert q["empty"] == ""
+    assert str(q).endswith("&flag&empty=&=x")
+
+
+def test_queryparams_get_index():
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert [q.get_index(i) for i in range(3)] == q.multi_items()
+    assert q.get_index(-1) == ("b", "789")
+    assert q.get_index(-3) == ("a", "123")
+    with pytest.raises(IndexError):
+        q.get_index(3)
+    with pytest.raises(IndexError):
+        q.get_index(-4)
+    with pytest.raises(IndexError):
+        httpx.QueryParams().get_index(0)
+
+
+def test_queryparams_reversed():
+    q = httpx.QueryParams("a=123&b=456&a=789&c=0")
+    assert list(reversed(q)) == ["c", "b", "a"]
+
+
+def test_queryparams_builder():
+    builder = httpx.QueryParamsBuilder({"a": "123"})
+    assert builder.add("a", 456).set("b", True) is builder
+    assert "a" in builder
+    assert len(builder) == 2
+    assert repr(builder) == "QueryParamsBuilder('a=123&a=456&b=true')"
+
+    q = builder.freeze()
+    assert q == httpx.QueryParams("a=123&a=456&b=true")
+
+    builder.add("a", "789").remove("b")
+    assert q == httpx.QueryParams("a=123&a=456&b=true")
+    assert builder.freeze() == httpx.QueryParams("a=123&a=456&a=789")
+
+
+def test_queryparams_builder_keeps_space_encoding():
+    params = httpx.QueryParams({"a": "b c"}).with_space_encoding("%20")
+    builder = httpx.QueryParamsBuilder(params)
+    assert str(builder.set("d", "e f").freeze()) == "a=b%20c&d=e%20f"
+
+
+@pytest.mark.parametrize(
+    "url,expected",
+    [
+        ("https://example.org/?a=123&b=456#c=789", "a=123&b=456"),
+        ("https://example.org/path?a=1?2", "a=1%3F2"),
+        ("https://example.org/#a=123", ""),
+        ("/path?a=123", "a=123"),
+        ("a=123", ""),
+        (httpx.URL("https://example.org/?a=%E9"), "a=%E9"),
+    ],
+)
+def test_queryparams_from_url(url, expected):
+    assert str(httpx.QueryParams.from_url(url)) == expected
+
+
+def test_queryparams_from_url_options():
+    url = "https://example.org/?flag&a=1;b=2"
+    q = httpx.QueryParams.from_url(url, separators="&;", keep_blank_values=True)
+    assert str(q) == "flag&a=1&b=2"
+
+
+def test_queryparams_to_dict():
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert q.to_dict() == {"a": "123", "b": "789"}
+    assert q.to_multidict() == {"a": ["123", "456"], "b": ["789"]}
+    assert httpx.QueryParams(q.to_multidict()) == q
+
+    q.to_multidict()["a"].append("0")
+    assert q.get_list("a") == ["123", "456"]
+
+
+def test_queryparams_nested():
+    value = {"a": {"b": [1, 2], "c": [{"d": True}, [3]]}, "e": None}
+    q = httpx.QueryParams.from_nested(value)
+    assert q.multi_items() == [
+        ("a[b][]", "1"),
+        ("a[b][]", "2"),
+        ("a[c][0][d]", "true"),
+        ("a[c][1][]", "3"),
+        ("e", ""),
+    ]
+    assert q.to_nested() == {
+        "a": {"b": ["1", "2"], "c": [{"d": "true"}, ["3"]]},
+        "e": "",
+    }
+
+    q = httpx.QueryParams.from_nested([("a", {"b": "1"}), ("a", {"c": "2"})])
+    assert str(q) == "a%5Bb%5D=1&a%5Bc%5D=2"
+
+
+@pytest.mark.parametrize(
+    "query,expected",
+    [
+        ("a[0]=1&a[1]=2", {"a": ["1", "2"]}),
+        ("a[1]=1&a[2]=2", {"a": {"1": "1", "2": "2"}}),
+        ("a[]=1&a[]=2", {"a": ["1", "2"]}),
+        ("a[][b]=1&a[][b]=2", {"a": [{"b": "1"}, {"b": "2"}]}),
+        ("a=1&a=2", {"a": "2"}),
+        ("a[b=1&[c]=2&d]=3", {"a[b": "1", "[c]": "2", "d]": "3"}),
+    ],
+)
+def test_queryparams_to_nested(query, expected):
+    assert httpx.QueryParams(query).to_nested() == expected
+
+
+def test_queryparams_to_nested_conflict():
+    with pytest.raises(ValueError):
+        httpx.QueryParams("a=1&a[b]=2").to_nested()
+    with pytest.raises(ValueError):
+        httpx.QueryParams("a[b]=2&a=1").to_nested()
+    with pytest.raises(ValueError):
+        httpx.QueryParams("a[]=1&a[b]=2").to_nested()
+
+
+@pytest.mark.parametrize(
+    "other",
+    [
+        "a=123&a=456&b=789",
+        b"b=789&a=123&a=456",
+        {"a": ["123", "456"], "b": 789},
+        [("a", "123"), ("a", "456"), ("b", "789")],
+        (("a", "123"), ("a", "456"), ("b", "789")),
+        types.MappingProxyType({"a": ["123", "456"], "b": "789"}),
+    ],
+)
+def test_queryparams_equal_to_other_types(other):
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert q == other
+    assert q != httpx.QueryParams(other).add("c", "0")
+
+
+@pytest.mark.parametrize(
+    "other", ["a=123", {"a": "123"}, [("a", "123")], [1, 2], 123, None]
+)
+def test_queryparams_not_equal_to_other_types(other):
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert q != other
+
+
+def test_queryparams_hash():
+    q = httpx.QueryParams("a=123&a=456&b=789")
+    assert hash(q) == hash(httpx.QueryParams("b=789&a=123&a=456"))
+    assert len({q, httpx.QueryParams([("b", "789"), ("a", "123"), ("a", "456")])}) == 1
+
+
+def test_queryparams_limits():
+    query = "a=1&b=2&&c=3"
+    assert httpx.QueryParams.parse(query, max_params=3, max_length=12) == query
+    q = httpx.QueryParams.parse(query.encode(), max_params=3, max_length=12)
+    assert q == query
+
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.parse(query, max_params=2)
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.parse(query, max_length=11)
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        httpx.QueryParams.from_url("https://example.org/?a&b", max_params=1)
+
+
+def test_url_params_limits():
+    url = httpx.URL("https://example.org/?a=1&b=2&c=3", max_params=2)
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        url.params
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        url.join("/other?a=1&b=2&c=3").params
+    assert url.copy_with(query=b"a=1").params == httpx.QueryParams("a=1")
+
+    url = httpx.URL("https://example.org/?a=1&b=2&c=3", max_query_length=10)
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        url.params
+    with pytest.raises(httpx.QueryParamsTooLarge):
+        pickle.loads(pickle.dumps(url)).params
+
+
+def test_queryparams_or_operator():
+    q = httpx.QueryParams("a=123&b=456")
+    assert q | {"b": "789", "c": 0} == "a=123&b=789&c=0"
+    assert q | "c=0" == "a=123&b=456&c=0"
+    assert {"b": "789", "c": 0} | q == "a=123&b=456&c=0"
+
+    original = q
+    q |= [("a", "0")]
+    assert q == "a=0&b=456"
+    assert original == "a=123&b=456"
+
+    with pytest.raises(TypeError):
+        q | 123
+
+
+def test_queryparams_shared_changes():
+    base = httpx.QueryParams("a=1&b=2&c=3")
+    first = base.set("a", "0").remove("b").add("d", "4")
+    second = base.add("a", "5").set_list("c", []).set("b", "6")
+    assert str(base) == "a=1&b=2&c=3"
+    assert str(first) == "a=0&c=3&d=4"
+    assert str(second) == "a=1&a=5&b=6"
+
+    q = base
+    expected = base.to_multidict()
+    for i in range(50):
+        key = "abcdefg"[i % 7]
+        if i % 3 == 0:
+            q = q.remove(key)
+            expected.pop(key, None)
+        else:
+            q = q.add(key, i)
+            expected[key] = expected.get(key, []) + [str(i)]
+    assert q.to_multidict() == expected
+    assert list(q) == list(expected)
+    assert str(base) == "a=1&b=2&c=3"
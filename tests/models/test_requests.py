@@ -136,6 +136,23 @@ def test_ignore_transfer_encoding_header_if_content_length_exists():
     assert request.headers["Content-Length"] == "4"
 
 
+@pytest.mark.parametrize(
+    "url,host",
+    [
+        ("http://EXAMPLE.org", "example.org"),
+        ("http://example.org:80", "example.org"),
+        ("https://example.org:443", "example.org"),
+        ("http://example.org:443", "example.org:443"),
+        ("http://中国.icom.museum", "xn--fiqs8s.icom.museum"),
+        ("http://[::1]", "[::1]"),
+        ("http://[::1]:8080", "[::1]:8080"),
+    ],
+)
+def test_host_header(url, host):
+    request = httpx.Request("GET", url)
+    assert request.headers["Host"] == host
+
+
 def test_override_host_header():
     headers = {"host": "1.2.3.4:80"}
 
@@ -1,3 +1,5 @@
+import gzip
+import io
 import json
 import pickle
 from unittest import mock
@@ -159,6 +161,37 @@ def test_response_fallback_to_autodetect():
     assert response.encoding is None
 
 
+@pytest.mark.parametrize(
+    "content_disposition,filename",
+    [
+        (None, None),
+        ("inline", None),
+        ('attachment; filename="report.pdf"', "report.pdf"),
+        ("attachment; filename*=UTF-8''%E2%82%AC%20rates.txt", "\u20ac rates.txt"),
+        ('attachment; filename="../../etc/passwd"', "passwd"),
+        ('attachment; filename="C:\\\\temp\\\\evil.exe"', "evil.exe"),
+        ('attachment; filename=".."', None),
+        ("attachment; filename*=UTF-8''a%00b%0D%0A.txt", "ab.txt"),
+        ("attachment; filename*=UTF-8''%0D%0A", None),
+    ],
+)
+def test_response_filename(content_disposition, filename):
+    headers = {}
+    if content_disposition is not None:
+        headers["Content-Disposition"] = content_disposition
+    response = httpx.Response(200, headers=headers)
+    assert response.filename == filename
+
+
+def test_response_retry_after():
+    response = httpx.Response(503, headers={"Retry-After": "120"})
+    assert response.retry_after == 120.0
+    response = httpx.Response(503, headers={"Retry-After": "later"})
+    assert response.retry_after is None
+    response = httpx.Response(503)
+    assert response.retry_after is None
+
+
 def test_response_no_charset_with_ascii_content():
     """
     A response with ascii encoded content should decode correctly,
@@ -892,3 +925,138 @@ async def test_response_async_streaming_picklable():
     assert pickle_response.is_stream_consumed is True
     assert pickle_response.content == b"Hello, world!"
     assert pickle_response.num_bytes_downloaded == 13
+
+
+def executable_body():
+    # A minimal DOS header, pointing to a PE signature at offset 64.
+    yield b"MZ\x90\x00" + b"\x00" * 56 + b"\x40\x00\x00\x00"
+    yield b"PE\x00\x00"
+    yield b"\x00" * 1024
+
+
+def test_response_content_sniffing_raises():
+    response = httpx.Response(
+        200,
+        headers={"Content-Type": "image/png"},
+        content=executable_body(),
+        content_sniffing="raise",
+    )
+    with pytest.raises(httpx.ContentTypeMismatch):
+        response.read()
+
+
+def test_response_content_sniffing_warns():
+    response = httpx.Response(
+        200,
+        headers={"Content-Type": "text/plain"},
+        content=[b"<!DOCTYPE html>", b"<html></html>"],
+        content_sniffing="warn",
+    )
+    with pytest.warns(UserWarning):
+        response.read()
+    assert response.content == b"<!DOCTYPE html><html></html>"
+
+
+def test_response_content_sniffing_matching_type():
+    response = httpx.Response(
+        200,
+        headers={"Content-Type": "application/zip"},
+        content=[b"PK\x03\x04", b"\x00" * 16],
+        content_sniffing="raise",
+    )
+    response.read()
+    assert response.content == b"PK\x03\x04" + b"\x00" * 16
+
+
+def test_response_content_sniffing_disabled():
+    response = httpx.Response(
+        200, headers={"Content-Type": "image/png"}, content=executable_body()
+    )
+    response.read()
+    assert response.content.startswith(b"MZ")
+
+
+def test_response_conditional_headers():
+    headers = {"ETag": '"abc"', "Last-Modified": "Wed, 21 Oct 2015 07:28:00 GMT"}
+    response = httpx.Response(200, headers=headers, content=b"Hello, world!")
+    assert response.conditional_headers() == {
+        "If-None-Match": '"abc"',
+        "If-Modified-Since": "Wed, 21 Oct 2015 07:28:00 GMT",
+    }
+    assert httpx.Response(200).conditional_headers() == {}
+
+
+def test_response_revalidate():
+    headers = {"ETag": '"abc"', "Cache-Control": "max-age=60"}
+    response = httpx.Response(200, headers=headers, content=b"Hello, world!")
+
+    not_modified = httpx.Response(304, headers={"Cache-Control": "max-age=120"})
+    revalidated = response.revalidate(not_modified)
+    assert revalidated.status_code == 200
+    assert revalidated.content == b"Hello, world!"
+    assert revalidated.headers["Cache-Control"] == "max-age=120"
+    assert revalidated.headers["ETag"] == '"abc"'
+    assert revalidated.headers["Content-Length"] == "13"
+
+    modified = httpx.Response(200, content=b"Goodbye, world!")
+    assert response.revalidate(modified) is modified
+
+
+def test_response_revalidate_decoded_content():
+    headers = {"Content-Encoding": "gzip"}
+    body = gzip.compress(b"Hello, world!")
+    response = httpx.Response(200, headers=headers, content=body)
+
+    revalidated = response.revalidate(httpx.Response(304))
+    assert revalidated.content == b"Hello, world!"
+    assert "Content-Encoding" not in revalidated.headers
+
+
+BYTERANGES_BODY = (
+    b"--boundary\r\n"
+    b"Content-Type: text/plain\r\n"
+    b"Content-Range: bytes 0-4/20\r\n"
+    b"\r\n"
+    b"Hello\r\n"
+    b"--boundary\r\n"
+    b"Content-Type: text/plain\r\n"
+    b"Content-Range: bytes 15-19/20\r\n"
+    b"\r\n"
+    b"World\r\n"
+    b"--boundary--\r\n"
+)
+
+
+def test_response_multipart_byte_ranges():
+    headers = {"Content-Type": "multipart/byteranges; boundary=boundary"}
+    response = httpx.Response(206, headers=headers, content=BYTERANGES_BODY)
+    assert list(response.iter_byte_ranges()) == [(0, b"Hello"), (15, b"World")]
+    assert response.read_byte_ranges() == {0: b"Hello", 15: b"World"}
+
+    file = io.BytesIO(b"." * 20)
+    response.write_byte_ranges(file)
+    assert file.getvalue() == b"Hello..........World"
+
+
+def test_response_single_byte_range():
+    headers = {"Content-Range": "bytes 10-14/20"}
+    response = httpx.Response(206, headers=headers, content=b"Hello")
+    assert response.read_byte_ranges() == {10: b"Hello"}
+
+    response = httpx.Response(200, content=b"Hello, world!")
+    assert response.read_byte_ranges() == {0: b"Hello, world!"}
+
+
+def test_response_byte_ranges_missing_content_range():
+    headers = {"Content-Type": "multipart/byteranges; boundary=boundary"}
+    content = b"--boundary\r\n\r\nHello\r\n--boundary--\r\n"
+    response = httpx.Response(206, headers=headers, content=content)
+    with pytest.raises(httpx.DecodingError):
+        response.read_byte_ranges()
+
+
+@pytest.mark.asyncio
+async def test_response_aread_byte_ranges():
+    headers = {"Content-Type": "multipart/byteranges; boundary=boundary"}
+    response = httpx.Response(206, headers=headers, content=BYTERANGES_BODY)
+    assert await response.aread_byte_ranges() == {0: b"Hello", 15: b"World"}
@@ -3,9 +3,13 @@ Unit tests for auth classes.
 
 Integration tests also exist in tests/client/test_auth.py
 """
+import base64
+import struct
+
 import pytest
 
 import httpx
+from httpx import _ntlm
 
 
 def test_basic_auth():
@@ -61,3 +65,100 @@ def test_digest_auth_with_401():
     response = httpx.Response(content=b"Hello, world!", status_code=200)
     with pytest.raises(StopIteration):
         flow.send(response)
+
+
+def ntlm_challenge_header(target_info: bytes = b"") -> str:
+    message = (
+        b"NTLMSSP\x00"
+        + struct.pack("<I", 2)
+        + struct.pack("<HHI", 0, 0, 48)
+        + struct.pack("<I", _ntlm.NEGOTIATE_FLAGS)
+        + bytes.fromhex("0123456789abcdef")
+        + b"\x00" * 8
+        + struct.pack("<HHI", len(target_info), len(target_info), 48)
+        + target_info
+    )
+    return "NTLM " + base64.b64encode(message).decode("ascii")
+
+
+def test_ntlm_auth():
+    auth = httpx.NTLMAuth(username="DOMAIN\\user", password="pass")
+    request = httpx.Request("GET", "https://www.example.com")
+
+    # The initial request should not include an auth header.
+    flow = auth.sync_auth_flow(request)
+    request = next(flow)
+    assert "Authorization" not in request.headers
+
+    # If a 401 response is returned, then a negotiate message is sent,
+    # with connection affinity for the remainder of the handshake.
+    headers = {"WWW-Authenticate": "NTLM"}
+    response = httpx.Response(status_code=401, headers=headers)
+    request = flow.send(response)
+    message = base64.b64decode(request.headers["Authorization"][5:])
+    assert message[:12] == b"NTLMSSP\x00\x01\x00\x00\x00"
+    assert request.extensions["connection_affinity"] == {}
+
+    # The challenge is answered with an authenticate message.
+    headers = {"WWW-Authenticate": ntlm_challenge_header()}
+    response = httpx.Response(status_code=401, headers=headers)
+    request = flow.send(response)
+    message = base64.b64decode(request.headers["Authorization"][5:])
+    assert message[:12] == b"NTLMSSP\x00\x03\x00\x00\x00"
+    assert "DOMAIN".encode("utf-16-le") in message
+    assert request.extensions["connection_affinity"] == {"release": True}
+
+    # No other requests are made.
+    response = httpx.Response(content=b"Hello, world!", status_code=200)
+    with pytest.raises(StopIteration):
+        flow.send(response)
+    assert "connection_affinity" not in request.extensions
+
+
+def test_ntlm_auth_malformed_challenge():
+    auth = httpx.NTLMAuth(username="user", password="pass")
+    request = httpx.Request("GET", "https://www.example.com")
+
+    flow = auth.sync_auth_flow(request)
+    request = next(flow)
+    request = flow.send(httpx.Response(401, headers={"WWW-Authenticate": "NTLM"}))
+
+    headers = {"WWW-Authenticate": "NTLM bm90LW50bG0="}
+    with pytest.raises(httpx.ProtocolError):
+        flow.send(httpx.Response(401, headers=headers))
+    assert "connection_affinity" not in request.extensions
+
+
+def test_ntlm_messages():
+    # Test vectors from the NTLMv2 authentication example in MS-NLMP.
+    assert _ntlm.md4(b"abc").hex() == "a448017aaf21d8525fc10ae87aa6729d"
+    assert _ntlm.nt_hash("Password").hex() == "a4f49c406510bdcab6824ee7c30fd852"
+
+    domain = "Domain".encode("utf-16-le")
+    server = "Server".encode("utf-16-le")
+    target_info = (
+        struct.pack("<HH", 2, len(domain))
+        + domain
+        + struct.pack("<HH", 1, len(server))
+        + server
+        + b"\x00\x00\x00\x00"
+    )
+    header = ntlm_challenge_header(target_info)
+    challenge = _ntlm.parse_challenge_message(base64.b64decode(header[5:]))
+    assert challenge.server_challenge == bytes.fromhex("0123456789abcdef")
+    assert challenge.target_info == target_info
+
+    message = _ntlm.authenticate_message(
+        challenge,
+        "User",
+        "Password",
+        domain="Domain",
+        client_challenge=b"\xaa" * 8,
+        timestamp=b"\x00" * 8,
+    )
+    lm_length, _, lm_offset = struct.unpack("<HHI", message[12:20])
+    nt_length, _, nt_offset = struct.unpack("<HHI", message[20:28])
+    lm_response = message[lm_offset : lm_offset + lm_length]
+    nt_proof = message[nt_offset : nt_offset + 16]
+    assert lm_response.hex() == "86c35097ac9cec102554764a57cccc19" + "aa" * 8
+    assert nt_proof.hex() == "68cd0ab851e51c96aabc927bebef6a1c"
@@ -0,0 +1,150 @@
+import random
+import typing
+
+import pytest
+
+import httpx
+
+
+@pytest.fixture
+def clock() -> typing.Iterator[httpx.FrozenClock]:
+    # Use a time far from the system clock, so that any code which does not use
+    # the configured clock is caught out.
+    clock = httpx.FrozenClock(time=1600000000.0)
+    httpx.set_clock(clock)
+    httpx.set_random(random.Random(1234))
+    try:
+        yield clock
+    finally:
+        httpx.set_clock(None)
+        httpx.set_random(None)
+
+
+def test_frozen_clock() -> None:
+    clock = httpx.FrozenClock(time=1600000000.0)
+    assert clock.time() == 1600000000.0
+    assert clock.monotonic() == 0.0
+
+    clock.advance(1.5)
+    assert clock.time() == 1600000001.5
+    assert clock.monotonic() == 1.5
+
+
+def test_multipart_boundary_is_reproducible(clock: httpx.FrozenClock) -> None:
+    files = {"file": ("example.txt", b"Hello, world!")}
+    first = httpx.Request("POST", "https://example.org/", files=files)
+
+    httpx.set_random(random.Random(1234))
+    second = httpx.Request("POST", "https://example.org/", files=files)
+
+    assert first.headers["Content-Type"] == second.headers["Content-Type"]
+    assert first.read() == second.read()
+
+
+def test_digest_auth_is_reproducible(clock: httpx.FrozenClock) -> None:
+    challenge = httpx.Response(
+        401,
+        headers={
+            "WWW-Authenticate": (
+                'Digest realm="httpx@example.org", qop="auth", '
+                'nonce="dcd98b7102dd2f0e8b11d0f600bfb0c093", algorithm="SHA-256"'
+            )
+        },
+    )
+
+    def authorization() -> str:
+        auth = httpx.DigestAuth(username="user", password="password")
+        flow = auth.sync_auth_flow(httpx.Request("GET", "https://example.org/"))
+        next(flow)
+        return flow.send(challenge).headers["Authorization"]
+
+    first = authorization()
+    httpx.set_random(random.Random(1234))
+    assert authorization() == first
+
+
+def test_robots_cache_expiry(clock: httpx.FrozenClock) -> None:
+    requests = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return httpx.Response(200, text="User-agent: *\nDisallow: /private/")
+
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(transport=transport) as client:
+        robots = httpx.Robots(client, max_age=60)
+        assert robots.is_allowed("https://example.org/")
+        clock.advance(59)
+        assert robots.is_allowed("https://example.org/")
+        assert len(requests) == 1
+
+        clock.advance(1)
+        assert robots.is_allowed("https://example.org/")
+        assert len(requests) == 2
+
+
+def test_persistent_cookie_expiry(clock: httpx.FrozenClock) -> None:
+    class MemoryCookieStorage(httpx.CookieStorage):
+        def __init__(self) -> None:
+            self.saved: typing.List[typing.Any] = []
+
+        def load(self) -> typing.List[typing.Any]:
+            return list(self.saved)
+
+        def save(self, cookies: typing.List[typing.Any]) -> None:
+            self.saved = list(cookies)
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        headers = [("Set-Cookie", "session=abc; Max-Age=3600")]
+        return httpx.Response(200, headers=headers)
+
+    storage = MemoryCookieStorage()
+    jar = httpx.PersistentCookieJar(storage)
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(cookies=jar, transport=transport) as client:
+        client.get("http://example.org/")
+    assert [cookie.name for cookie in storage.saved] == ["session"]
+
+    clock.advance(7200)
+    jar.save()
+    assert storage.saved == []
+
+    assert [cookie.name for cookie in jar] == ["session"]
+    jar.clear_expired_cookies()
+    assert list(jar) == []
+
+
+def test_cookie_deleted_by_max_age(clock: httpx.FrozenClock) -> None:
+    def handler(request: httpx.Request) -> httpx.Response:
+        max_age = "0" if request.url.path == "/logout" else "3600"
+        headers = [("Set-Cookie", f"session=abc; Max-Age={max_age}")]
+        return httpx.Response(200, headers=headers)
+
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(transport=transport) as client:
+        client.get("http://example.org/")
+        assert list(client.cookies) == ["session"]
+
+        client.get("http://example.org/logout")
+        assert list(client.cookies) == []
+
+
+def test_cookie_expiry(clock: httpx.FrozenClock) -> None:
+    def handler(request: httpx.Request) -> httpx.Response:
+        headers = [
+            ("Set-Cookie", "session=abc; Max-Age=3600"),
+            # Already expired by the system clock, but not by the frozen clock.
+            ("Set-Cookie", "theme=dark; Expires=Wed, 01 Jan 2025 00:00:00 GMT"),
+        ]
+        return httpx.Response(200, headers=headers)
+
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(transport=transport) as client:
+        client.get("http://example.org/")
+        assert sorted(client.cookies) == ["session", "theme"]
+        request = client.build_request("GET", "http://example.org/")
+        assert request.headers["Cookie"] == "session=abc; theme=dark"
+
+        clock.advance(3600)
+        request = client.build_request("GET", "http://example.org/")
+        assert request.headers["Cookie"] == "theme=dark"
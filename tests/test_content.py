@@ -423,3 +423,74 @@ async def test_response_aiterator_content():
 def test_response_invalid_argument():
     with pytest.raises(TypeError):
         encode_response(123)  # type: ignore
+
+
+def test_file_content(tmp_path):
+    path = tmp_path / "example.txt"
+    path.write_bytes(b"Hello, world!")
+
+    with path.open("rb") as file:
+        headers, stream = encode_request(content=file)
+        assert headers == {"Content-Length": "13"}
+        chunks = list(stream)
+        assert b"".join(chunks) == b"Hello, world!"
+        assert all(isinstance(chunk, bytes) for chunk in chunks)
+        assert file.tell() == 13
+
+
+def test_file_content_memory_mapped_windows(tmp_path, monkeypatch):
+    path = tmp_path / "example.txt"
+    path.write_bytes(b"Hello, world!")
+    monkeypatch.setattr(httpx._utils, "MMAP_WINDOW_SIZE", 5)
+
+    with path.open("rb") as file:
+        file.seek(2)
+        chunks = list(httpx._utils.iter_file(file, use_mmap=True))
+        assert chunks == [b"llo, ", b"world", b"!"]
+        assert all(isinstance(chunk, memoryview) for chunk in chunks)
+        assert file.tell() == 13
+
+    with path.open("rb") as file:
+        request = httpx.Request("POST", "https://example.org", content=file)
+        assert all(isinstance(chunk, bytes) for chunk in request.stream)
+
+    with path.open("rb") as file:
+        request = httpx.Request(
+            "POST", "https://example.org", files={"file": file}, mmap_uploads=True
+        )
+        chunks = list(request.stream)
+        assert any(isinstance(chunk, memoryview) for chunk in chunks)
+        assert b"Hello, world!" in b"".join(chunks)
+
+
+def test_file_content_fallback(tmp_path):
+    path = tmp_path / "example.txt"
+    path.write_bytes(b"")
+
+    # Empty files have nothing to map.
+    with path.open("rb") as file:
+        assert list(httpx._utils.iter_file(file, use_mmap=True)) == []
+
+    # In-memory streams cannot be memory mapped.
+    stream = io.BytesIO(b"Hello, world!")
+    assert httpx._utils.mappable_fileno(stream) is None
+    assert list(httpx._utils.iter_file(stream, use_mmap=True)) == [b"Hello, world!"]
+
+
+def test_multipart_file_content(tmp_path):
+    path = tmp_path / "example.txt"
+    path.write_bytes(b"<file content>")
+
+    with path.open("rb") as file:
+        headers, stream = encode_request(files={"file": file}, boundary=b"+++")
+        assert b"".join(stream) == b"".join(
+            [
+                b"--+++\r\n",
+                b'Content-Disposition: form-data; name="file"; '
+                b'filename="example.txt"\r\n',
+                b"Content-Type: text/plain\r\n",
+                b"\r\n",
+                b"<file content>\r\n",
+                b"--+++--\r\n",
+            ]
+        )
@@ -0,0 +1,134 @@
+import contextlib
+import json
+import sqlite3
+import typing
+
+import pytest
+
+import httpx
+
+
+def set_cookies(request: httpx.Request) -> httpx.Response:
+    headers = [
+        ("Set-Cookie", "session=abc123; path=/"),
+        ("Set-Cookie", "token=xyz; domain=example.org; path=/"),
+        ("Set-Cookie", "expired=1; expires=Thu, 01 Jan 1970 00:00:00 GMT"),
+    ]
+    return httpx.Response(200, headers=headers)
+
+
+def echo_cookies(request: httpx.Request) -> httpx.Response:
+    return httpx.Response(200, json={"cookies": request.headers.get("Cookie")})
+
+
+@pytest.mark.parametrize(
+    "storage_class", [httpx.JSONCookieStorage, httpx.SQLiteCookieStorage]
+)
+def test_cookies_persisted(storage_class: typing.Any, tmp_path: typing.Any) -> None:
+    path = tmp_path / "cookies"
+
+    jar = httpx.PersistentCookieJar(storage_class(path))
+    transport = httpx.MockTransport(set_cookies)
+    with httpx.Client(cookies=jar, transport=transport) as client:
+        client.get("http://example.org/")
+
+    jar = httpx.PersistentCookieJar(storage_class(path))
+    transport = httpx.MockTransport(echo_cookies)
+    with httpx.Client(cookies=jar, transport=transport) as client:
+        assert client.cookies["session"] == "abc123"
+        assert client.cookies.get("token", domain=".example.org") == "xyz"
+        assert "expired" not in client.cookies
+
+        response = client.get("http://example.org/")
+        assert response.json() == {"cookies": "session=abc123; token=xyz"}
+
+
+@pytest.mark.parametrize(
+    "storage_class", [httpx.JSONCookieStorage, httpx.SQLiteCookieStorage]
+)
+def test_cookies_persisted_on_change(
+    storage_class: typing.Any, tmp_path: typing.Any
+) -> None:
+    storage = storage_class(tmp_path / "cookies")
+    cookies = httpx.Cookies(httpx.PersistentCookieJar(storage))
+
+    cookies.set("example-name", "example-value", domain="example.org")
+    assert [cookie.name for cookie in storage.load()] == ["example-name"]
+
+    cookies.delete("example-name")
+    assert storage.load() == []
+
+
+def test_custom_cookie_storage() -> None:
+    class MemoryCookieStorage(httpx.CookieStorage):
+        def __init__(self) -> None:
+            self.saved: typing.List[typing.Any] = []
+
+        def load(self) -> typing.List[typing.Any]:
+            return list(self.saved)
+
+        def save(self, cookies: typing.List[typing.Any]) -> None:
+            self.saved = list(cookies)
+
+    storage = MemoryCookieStorage()
+    jar = httpx.PersistentCookieJar(storage)
+    transport = httpx.MockTransport(set_cookies)
+    with httpx.Client(cookies=jar, transport=transport) as client:
+        client.get("http://example.org/")
+
+    assert sorted(cookie.name for cookie in storage.saved) == ["session", "token"]
+
+
+def test_sqlite_cookie_storage_updates_changed_rows(tmp_path: typing.Any) -> None:
+    path = tmp_path / "cookies.db"
+    cookies = httpx.Cookies(httpx.PersistentCookieJar(httpx.SQLiteCookieStorage(path)))
+    cookies.set("a", "1", domain="example.org")
+    cookies.set("b", "2", domain="example.org")
+
+    def rowids() -> typing.Dict[str, int]:
+        with contextlib.closing(sqlite3.connect(path)) as connection:
+            rows = connection.execute("SELECT name, rowid FROM cookies").fetchall()
+        return dict(rows)
+
+    before = rowids()
+    cookies.set("b", "3", domain="example.org")
+    after = rowids()
+    assert after["a"] == before["a"]
+    assert after["b"] != before["b"]
+
+
+def test_callable_cookie_storage() -> None:
+    stored = "[]"
+
+    def load() -> typing.List[typing.Dict[str, typing.Any]]:
+        return json.loads(stored)
+
+    def save(cookies: typing.List[typing.Dict[str, typing.Any]]) -> None:
+        nonlocal stored
+        stored = json.dumps(cookies)
+
+    storage = httpx.CallableCookieStorage(load=load, save=save)
+    jar = httpx.PersistentCookieJar(storage)
+    transport = httpx.MockTransport(set_cookies)
+    with httpx.Client(cookies=jar, transport=transport) as client:
+        client.get("http://example.org/")
+    assert sorted(cookie["name"] for cookie in json.loads(stored)) == [
+        "session",
+        "token",
+    ]
+
+    cookies = httpx.Cookies(httpx.PersistentCookieJar(storage))
+    assert cookies["session"] == "abc123"
+
+
+def test_expired_cookies_not_loaded() -> None:
+    class ExpiredCookieStorage(httpx.CookieStorage):
+        def load(self) -> typing.List[typing.Any]:
+            cookies = httpx.Cookies()
+            cookies.set("example-name", "example-value", domain="example.org")
+            cookie = next(iter(cookies.jar))
+            cookie.expires = 1
+            return [cookie]
+
+    jar = httpx.PersistentCookieJar(ExpiredCookieStorage())
+    assert list(jar) == []
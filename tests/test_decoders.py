@@ -11,6 +11,7 @@ from httpx._decoders import (
     GZipDecoder,
     IdentityDecoder,
     LineDecoder,
+    MultipartDecoder,
     TextChunker,
     TextDecoder,
 )
@@ -356,3 +357,44 @@ def test_invalid_content_encoding_header():
         content=body,
     )
     assert response.content == body
+
+
+def test_multipart_decoder():
+    body = (
+        b"preamble\r\n"
+        b"--boundary\r\n"
+        b"Content-Range: bytes 0-4/20\r\n"
+        b"\r\n"
+        b"Hello\r\n"
+        b"--boundary\r\n"
+        b"\r\n"
+        b"World\r\n"
+        b"--boundary--\r\n"
+        b"epilogue"
+    )
+
+    # The result is the same, however the body is split into chunks.
+    for chunk_size in (1, 5, len(body)):
+        decoder = MultipartDecoder(b"boundary")
+        events = []
+        for i in range(0, len(body), chunk_size):
+            events.extend(decoder.decode(body[i : i + chunk_size]))
+        events.extend(decoder.flush())
+
+        parts = []
+        for event in events:
+            if isinstance(event, list):
+                parts.append((event, b""))
+            else:
+                parts[-1] = (parts[-1][0], parts[-1][1] + event)
+        assert parts == [
+            ([(b"Content-Range", b"bytes 0-4/20")], b"Hello"),
+            ([], b"World"),
+        ]
+
+
+def test_multipart_decoder_incomplete():
+    decoder = MultipartDecoder(b"boundary")
+    decoder.decode(b"--boundary\r\n\r\nHello")
+    with pytest.raises(httpx.DecodingError):
+        decoder.flush()
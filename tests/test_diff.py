@@ -0,0 +1,86 @@
+import pytest
+
+import httpx
+
+
+def test_diff_equivalent_requests():
+    first = httpx.Request(
+        "GET", "https://example.org/?a=1&b=2", headers={"X-Test": "1", "Y": "2"}
+    )
+    second = httpx.Request(
+        "get", "https://example.org/?b=2&a=1", headers={"y": "2", "x-test": "1"}
+    )
+    assert httpx.diff(first, second) == ""
+
+
+def test_diff_requests():
+    first = httpx.Request("GET", "https://example.org/", headers={"X-Test": "1"})
+    second = httpx.Request("POST", "https://example.org/path", content=b"Hello")
+    assert httpx.diff(first, second) == "\n".join(
+        [
+            "method: 'GET' != 'POST'",
+            "url: 'https://example.org/' != 'https://example.org/path'",
+            "header 'content-length': [] != ['5']",
+            "header 'x-test': ['1'] != []",
+            "content:",
+            "    --- expected",
+            "    +++ actual",
+            "    @@ -0,0 +1 @@",
+            "    +Hello",
+        ]
+    )
+
+
+def test_diff_json_content():
+    first = httpx.Response(200, json={"a": 1, "b": [1, 2]})
+    second = httpx.Response(200, content=b'{"b": [1, 2], "a": 1}')
+    differences = httpx.diff(first, second)
+    assert differences == "header 'content-type': ['application/json'] != []"
+
+    second = httpx.Response(201, json={"a": 2, "b": [1, 2]})
+    assert httpx.diff(first, second) == "\n".join(
+        [
+            "status_code: 200 != 201",
+            "json:",
+            "    --- expected",
+            "    +++ actual",
+            "    @@ -1,5 +1,5 @@",
+            "     {",
+            '    -  "a": 1,',
+            '    +  "a": 2,',
+            '       "b": [',
+            "         1,",
+            "         2",
+        ]
+    )
+
+
+def test_diff_binary_content():
+    first = httpx.Response(200, content=b"\xff\x00")
+    second = httpx.Response(200, content=b"\xff\x01")
+    assert httpx.diff(first, second) == "content: b'\\xff\\x00' != b'\\xff\\x01'"
+
+
+def test_diff_invalid_types():
+    request = httpx.Request("GET", "https://example.org/")
+    response = httpx.Response(200)
+    with pytest.raises(TypeError):
+        httpx.diff(request, response)
+
+
+def test_mock_transport_assert_requested():
+    transport = httpx.MockTransport(lambda request: httpx.Response(200))
+
+    with httpx.Client(transport=transport) as client:
+        expected = client.build_request("GET", "https://example.org/?a=1&b=2")
+        with pytest.raises(AssertionError, match="No requests have been sent."):
+            transport.assert_requested(expected)
+
+        client.get("https://example.org/", params={"b": 2, "a": 1})
+        transport.assert_requested(expected)
+
+        client.post("https://example.org/", content=b"Hello")
+        expected = client.build_request("DELETE", "https://example.org/")
+        with pytest.raises(AssertionError) as exc_info:
+            transport.assert_requested(expected)
+        assert "method: 'DELETE' != 'POST'" in str(exc_info.value)
@@ -0,0 +1,122 @@
+import socket
+import struct
+
+import pytest
+
+import httpx
+from httpx._transports.doh import (
+    DNS_TYPE_A,
+    DNS_TYPE_AAAA,
+    build_dns_query,
+    parse_dns_response,
+)
+
+RECORDS = {
+    DNS_TYPE_A: [socket.inet_pton(socket.AF_INET, "93.184.216.34")],
+    DNS_TYPE_AAAA: [socket.inet_pton(socket.AF_INET6, "2606:2800:220:1::248")],
+}
+
+
+def dns_response(query: bytes, rdatas: list, rcode: int = 0, ttl: int = 300) -> bytes:
+    record_type = struct.unpack("!H", query[-4:-2])[0]
+    header = struct.pack("!HHHHHH", 0, 0x8180 | rcode, 1, len(rdatas), 0, 0)
+    answers = b"".join(
+        # A compression pointer back to the question name, at offset 12.
+        b"\xc0\x0c" + struct.pack("!HHIH", record_type, 1, ttl, len(rdata)) + rdata
+        for rdata in rdatas
+    )
+    return header + query[12:] + answers
+
+
+def dns_handler(request: httpx.Request) -> httpx.Response:
+    assert request.method == "POST"
+    assert request.headers["Content-Type"] == "application/dns-message"
+    query = request.read()
+    record_type = struct.unpack("!H", query[-4:-2])[0]
+    content = dns_response(query, RECORDS[record_type])
+    return httpx.Response(
+        200, headers={"Content-Type": "application/dns-message"}, content=content
+    )
+
+
+def test_build_dns_query():
+    query = build_dns_query("example.com", DNS_TYPE_A)
+    assert query == (
+        b"\x00\x00\x01\x00\x00\x01\x00\x00\x00\x00\x00\x00"
+        b"\x07example\x03com\x00\x00\x01\x00\x01"
+    )
+    assert build_dns_query("example.com.", DNS_TYPE_A) == query
+
+
+@pytest.mark.parametrize(
+    "host", ["", ".", "example..com", ".example.com", "a" * 64 + ".com", "a." * 128]
+)
+def test_build_invalid_dns_query(host):
+    with pytest.raises(ValueError):
+        build_dns_query(host, DNS_TYPE_A)
+
+
+def test_parse_dns_response():
+    query = build_dns_query("example.com", DNS_TYPE_A)
+    response = dns_response(query, RECORDS[DNS_TYPE_A], ttl=60)
+    assert parse_dns_response(response) == [("93.184.216.34", 60)]
+
+
+def test_parse_malformed_dns_response():
+    with pytest.raises(socket.gaierror):
+        parse_dns_response(b"\x00\x00")
+
+
+def test_doh_resolve():
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(dns_handler))
+    assert resolver.resolve("example.com") == [
+        "93.184.216.34",
+        "2606:2800:220:1::248",
+    ]
+
+
+@pytest.mark.parametrize("host", ["127.0.0.1", "::1"])
+def test_doh_resolve_ip_address(host):
+    def handler(request: httpx.Request) -> httpx.Response:
+        raise AssertionError("IP addresses should not be queried.")
+
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(handler))
+    assert resolver.resolve(host) == [host]
+
+
+def test_doh_resolve_is_cached():
+    calls = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        calls.append(request)
+        return dns_handler(request)
+
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(handler))
+    resolver.resolve("example.com")
+    resolver.resolve("example.com")
+    assert len(calls) == 2  # One A query, and one AAAA query.
+
+
+def test_doh_resolve_nxdomain():
+    def handler(request: httpx.Request) -> httpx.Response:
+        content = dns_response(request.read(), [], rcode=3)
+        return httpx.Response(200, content=content)
+
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(handler))
+    with pytest.raises(socket.gaierror):
+        resolver.resolve("example.invalid")
+
+
+def test_doh_resolve_invalid_hostname():
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(dns_handler))
+    with pytest.raises(socket.gaierror):
+        resolver.resolve("example..com")
+
+
+def test_doh_resolve_server_error():
+    def handler(request: httpx.Request) -> httpx.Response:
+        return httpx.Response(503)
+
+    resolver = httpx.DoHResolver(transport=httpx.MockTransport(handler))
+    with pytest.raises(socket.gaierror):
+        resolver.resolve("example.com")
@@ -0,0 +1,132 @@
+import threading
+import time
+import typing
+
+import pytest
+
+import httpx
+
+
+class Server:
+    """
+    A mock server that is slow to respond to the first request it receives.
+    """
+
+    def __init__(self, slow_first_request: bool) -> None:
+        self.slow_first_request = slow_first_request
+        self.requests = []
+        self.lock = threading.Lock()
+
+    def handler(self, request: httpx.Request) -> httpx.Response:
+        with self.lock:
+            self.requests.append(request)
+            index = len(self.requests)
+        if index == 1 and self.slow_first_request:
+            time.sleep(0.5)
+        body = request.content.decode()
+        return httpx.Response(200, json={"index": index, "body": body})
+
+
+def test_hedging_not_required():
+    server = Server(slow_first_request=False)
+    transport = httpx.HedgingTransport(httpx.MockTransport(server.handler), delay=1)
+    with httpx.Client(transport=transport) as client:
+        response = client.get("http://example.org/")
+
+    assert response.json() == {"index": 1, "body": ""}
+    assert len(server.requests) == 1
+
+
+def test_hedged_request():
+    server = Server(slow_first_request=True)
+    transport = httpx.HedgingTransport(httpx.MockTransport(server.handler), delay=0.01)
+    with httpx.Client(transport=transport) as client:
+        response = client.put("http://example.org/", content=b"data")
+
+    assert response.json() == {"index": 2, "body": "data"}
+    assert len(server.requests) == 2
+
+
+def test_hedged_request_with_hedge_transport():
+    server = Server(slow_first_request=True)
+    hedge_server = Server(slow_first_request=False)
+    transport = httpx.HedgingTransport(
+        httpx.MockTransport(server.handler),
+        delay=0.01,
+        hedge_transport=httpx.MockTransport(hedge_server.handler),
+    )
+    with httpx.Client(transport=transport) as client:
+        response = client.get("http://example.org/")
+
+    assert response.json() == {"index": 1, "body": ""}
+    assert len(server.requests) == 1
+    assert len(hedge_server.requests) == 1
+
+
+def test_hedged_request_failure():
+    server = Server(slow_first_request=True)
+
+    def raise_error(request: httpx.Request) -> httpx.Response:
+        raise httpx.ConnectError("Connection failed", request=request)
+
+    transport = httpx.HedgingTransport(
+        httpx.MockTransport(server.handler),
+        delay=0.01,
+        hedge_transport=httpx.MockTransport(raise_error),
+    )
+    with httpx.Client(transport=transport) as client:
+        response = client.get("http://example.org/")
+
+    assert response.json() == {"index": 1, "body": ""}
+
+
+def test_hedged_request_both_fail():
+    def raise_error(request: httpx.Request) -> httpx.Response:
+        raise httpx.ConnectError("Connection failed", request=request)
+
+    transport = httpx.HedgingTransport(httpx.MockTransport(raise_error), delay=0.01)
+    with httpx.Client(transport=transport) as client:
+        with pytest.raises(httpx.ConnectError):
+            client.get("http://example.org/")
+
+
+def test_non_idempotent_request_is_not_hedged():
+    server = Server(slow_first_request=True)
+    transport = httpx.HedgingTransport(httpx.MockTransport(server.handler), delay=0.01)
+    with httpx.Client(transport=transport) as client:
+        response = client.post("http://example.org/", content=b"data")
+
+    assert response.json() == {"index": 1, "body": "data"}
+    assert len(server.requests) == 1
+
+
+def test_hedged_request_closes_slower_response():
+    release = threading.Event()
+    closed = threading.Event()
+
+    class Stream(httpx.SyncByteStream):
+        def __iter__(self) -> typing.Iterator[bytes]:
+            yield b"slow"
+
+        def close(self) -> None:
+            closed.set()
+
+    def slow_handler(request: httpx.Request) -> httpx.Response:
+        release.wait(timeout=5)
+        return httpx.Response(200, stream=Stream())
+
+    def fast_handler(request: httpx.Request) -> httpx.Response:
+        return httpx.Response(200, content=b"fast")
+
+    transport = httpx.HedgingTransport(
+        httpx.MockTransport(slow_handler),
+        delay=0.01,
+        hedge_transport=httpx.MockTransport(fast_handler),
+    )
+    with httpx.Client(transport=transport) as client:
+        response = client.get("http://example.org/")
+        assert response.content == b"fast"
+        assert not closed.is_set()
+
+        release.set()
+        assert closed.wait(timeout=5)
@@ -0,0 +1,121 @@
+import pytest
+
+import httpx
+
+ROBOTS_TXT = """
+# Example robots.txt
+User-agent: *
+Disallow: /private/
+Allow: /private/public$
+Crawl-delay: 2
+
+User-agent: ExampleBot
+User-agent: OtherBot
+Disallow: /
+Allow: /*.html
+
+Sitemap: https://example.org/sitemap.xml
+"""
+
+
+@pytest.mark.parametrize(
+    ["url", "user_agent", "allowed"],
+    [
+        ("https://example.org/", "python-httpx/0.18.1", True),
+        ("https://example.org/private/", "python-httpx/0.18.1", False),
+        ("https://example.org/private/public", "python-httpx/0.18.1", True),
+        ("https://example.org/private/public?q=1", "python-httpx/0.18.1", False),
+        ("https://example.org/page.html", "ExampleBot/1.0", True),
+        ("https://example.org/page", "examplebot", False),
+        ("https://example.org/page", "OtherBot", False),
+        ("https://example.org/robots.txt", "ExampleBot", True),
+    ],
+)
+def test_robots_txt_is_allowed(url, user_agent, allowed):
+    robots_txt = httpx.RobotsTxt(ROBOTS_TXT)
+    assert robots_txt.is_allowed(url, user_agent) is allowed
+
+
+def test_robots_txt_longest_match():
+    robots_txt = httpx.RobotsTxt(
+        "User-agent: *\nDisallow: /folder\nAllow: /folder/page\nDisallow: /*.gif$"
+    )
+    assert not robots_txt.is_allowed("https://example.org/folder/", "bot")
+    assert robots_txt.is_allowed("https://example.org/folder/page", "bot")
+    assert not robots_txt.is_allowed("https://example.org/image.gif", "bot")
+    assert robots_txt.is_allowed("https://example.org/page.gif?size=2", "bot")
+
+
+def test_robots_txt_percent_encoding():
+    robots_txt = httpx.RobotsTxt("User-agent: *\nDisallow: /a b/%7euser")
+    assert not robots_txt.is_allowed("https://example.org/a%20b/~user", "bot")
+
+
+def test_robots_txt_crawl_delay_and_sitemaps():
+    robots_txt = httpx.RobotsTxt(ROBOTS_TXT)
+    assert robots_txt.crawl_delay("python-httpx") == 2.0
+    assert robots_txt.crawl_delay("ExampleBot") is None
+    assert robots_txt.sitemaps == ["https://example.org/sitemap.xml"]
+
+
+def test_robots_txt_empty():
+    robots_txt = httpx.RobotsTxt("")
+    assert robots_txt.is_allowed("https://example.org/", "bot")
+
+
+def robots_app(request: httpx.Request) -> httpx.Response:
+    if request.url.host == "missing.org":
+        return httpx.Response(404)
+    elif request.url.host == "error.org":
+        return httpx.Response(503)
+    elif request.url.path == "/robots.txt":
+        return httpx.Response(200, text=ROBOTS_TXT)
+    return httpx.Response(200, text="Hello, world!")
+
+
+def test_robots():
+    requests = []
+
+    def handler(request: httpx.Request) -> httpx.Response:
+        requests.append(request)
+        return robots_app(request)
+
+    headers = {"User-Agent": "ExampleBot/1.0"}
+    transport = httpx.MockTransport(handler)
+    with httpx.Client(headers=headers, transport=transport) as client:
+        robots = httpx.Robots(client)
+        assert robots.is_allowed("https://example.org/page.html")
+        assert not robots.is_allowed("https://example.org/page")
+        assert robots.is_allowed("https://example.org/page", user_agent="other")
+        assert robots.crawl_delay("https://example.org/", user_agent="other") == 2.0
+
+    # The robots.txt file is fetched once, and then cached.
+    assert [str(request.url) for request in requests] == [
+        "https://example.org/robots.txt"
+    ]
+
+
+def test_robots_unavailable_or_unreachable():
+    transport = httpx.MockTransport(robots_app)
+    with httpx.Client(transport=transport) as client:
+        robots = httpx.Robots(client)
+        assert robots.is_allowed("https://missing.org/page")
+        assert not robots.is_allowed("https://error.org/page")
+
+
+def test_robots_base_url():
+    transport = httpx.MockTransport(robots_app)
+    base_url = "https://example.org"
+    with httpx.Client(base_url=base_url, transport=transport) as client:
+        robots = httpx.Robots(client)
+        assert not robots.is_allowed("/private/", user_agent="bot")
+
+
+@pytest.mark.usefixtures("async_environment")
+async def test_async_robots():
+    transport = httpx.MockTransport(robots_app)
+    async with httpx.AsyncClient(transport=transport) as client:
+        robots = httpx.AsyncRobots(client)
+        assert await robots.is_allowed("https://example.org/", user_agent="bot")
+        assert not await robots.is_allowed("https://example.org/private/", "bot")
+        assert not await robots.is_allowed("https://error.org/page", "bot")